@@ -1,6 +1,50 @@
 use anchor_lang::prelude::*;
 
+use crate::states::AdminRegistry;
+
 #[constant]
 pub const GOLD_USD_PRICE_FEED: &str =
     "0x765d2ba906dbc32ca17cc11f5310a89e9ee1f6420508c63861f2f8ba4ee34bb2";
 pub const MAX_AGE: u64 = 100;
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+pub const DEFAULT_RESERVE_RATIO_BPS: u16 = 1_000; //10% OF POOL ASSETS HELD BACK FOR WITHDRAWALS
+pub const DEFAULT_MAX_UTILIZATION_BPS: u16 = 9_000;
+pub const DEFAULT_GRACE_PERIOD: i64 = 7 * 86_400; //7 DAYS
+pub const DEFAULT_PENALTY_APR_BPS: u16 = 500;
+pub const DEFAULT_LATE_FEE_BPS: u16 = 500; //5% OF THE MISSED INSTALLMENT
+pub const DEFAULT_MAX_MISSED_INSTALLMENTS: u16 = 3;
+pub const DEFAULT_RESERVE_FACTOR_BPS: u16 = 1_000; //10% OF BORROWER INTEREST GOES TO PROTOCOL RESERVES
+pub const DEFAULT_FLASH_FEE_BPS: u16 = 9; //0.09% OF EVERY FLASH LOAN
+pub const DEFAULT_VERIFICATION_SLA: i64 = 3 * 86_400; //3 DAYS FOR AN ADMIN TO DECIDE ON A VERIFICATION
+pub const DEFAULT_VERIFICATION_QUORUM: u8 = 1; //MATCHING ATTESTATIONS NEEDED TO DECIDE A VERIFICATION
+pub const DEFAULT_ATTESTATION_TOLERANCE_BPS: u16 = 50; //ATTESTED WEIGHT/PURITY WITHIN 0.5% COUNT AS AGREEING
+pub const MAX_ATTESTATIONS: usize = AdminRegistry::MAX_ADMINS; //ONE PER ADMIN, EVERY ADMIN CAN ALWAYS ATTEST ONCE
+
+//ASSAY REPORTS
+pub const MAX_REPORT_URI_LEN: usize = 128;
+pub const MAX_BAR_SERIAL_LEN: usize = 32;
+pub const MAX_REFINERY_LEN: usize = 64;
+
+//REPAYMENT TYPES
+pub const REPAYMENT_TYPE_BULLET: u8 = 0; //WHOLE DEBT DUE AT MATURITY
+pub const REPAYMENT_TYPE_INSTALLMENT: u8 = 1; //EQUAL MONTHLY PRINCIPAL + INTEREST INSTALLMENTS
+pub const INSTALLMENT_INTERVAL: i64 = 30 * 86_400; //ONE INSTALLMENT EVERY 30 DAYS
+
+//ORIGINATION FEE MODES
+pub const ORIGINATION_FEE_MODE_CAPITALIZE: u8 = 0; //FEE ADDED TO THE DEBT, PAID BACK ON REPAY
+pub const ORIGINATION_FEE_MODE_DEDUCT: u8 = 1; //FEE TAKEN OUT OF THE DISBURSEMENT, STRAIGHT TO THE FEE VAULT
+
+//INTEREST MODES
+pub const INTEREST_MODE_SIMPLE: u8 = 0; //LINEAR APR ON THE PRINCIPAL
+pub const INTEREST_MODE_COMPOUND_PER_SECOND: u8 = 1; //CONTINUOUS COMPOUNDING, e^(APR * t)
+pub const INTEREST_MODE_COMPOUND_PERIODIC: u8 = 2; //INTEREST CAPITALIZED EVERY COMPOUNDING PERIOD
+
+//RATE TYPES
+pub const RATE_TYPE_FIXED: u8 = 0; //APR LOCKED AT ORIGINATION, PRICED WITH THE FIXED RATE PREMIUM
+pub const RATE_TYPE_VARIABLE: u8 = 1; //APR FOLLOWS POOL UTILIZATION
+
+//RATE MODELS
+pub const RATE_MODEL_STEP: u8 = 0; //RATE OF THE HIGHEST UTILIZATION TIER REACHED
+pub const RATE_MODEL_KINKED: u8 = 1; //LINEAR UP TO THE OPTIMAL UTILIZATION, STEEPER SLOPE ABOVE IT
+pub const RATE_MODEL_PIECEWISE_LINEAR: u8 = 2; //INTERPOLATED BETWEEN UP TO MAX_RATE_POINTS POINTS
+pub const MAX_RATE_POINTS: usize = 8;
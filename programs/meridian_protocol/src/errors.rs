@@ -48,4 +48,88 @@ pub enum Errors {
     InsufficientLiquidityToBorrow,
     #[msg("Cannot Liquidate the asset when the value of collateral is healthy i.e above 1 || Cannot Liquidate When the Loan Is Already Repaid")]
     CannotLiquidate,
+    #[msg("Math Overflow")]
+    MathOverflow,
+    #[msg("Pool has no assets backing the outstanding LP shares")]
+    PoolInsolvent,
+    #[msg("Null Withdrawals Not Allowed")]
+    NullWithdrawalNotAllowed,
+    #[msg("Not enough lp shares in the lender position")]
+    InsufficientShares,
+    #[msg("Slippage exceeded, withdrawal would be worse than the limit")]
+    SlippageExceeded,
+    #[msg("Not enough idle liquidity in pool to withdraw")]
+    InsufficientLiquidityToWithdraw,
+    #[msg("Withdrawal epoch not over yet, cannot claim")]
+    WithdrawalEpochNotOver,
+    #[msg("Withdrawal requests are claimed in order, this request isn't at the head of the queue")]
+    NotHeadOfWithdrawalQueue,
+    #[msg("Max Deposit Lots Reached, Withdraw Before Depositing Again")]
+    MaxDepositLots,
+    #[msg("Null Borrows Not Allowed")]
+    NullBorrowNotAllowed,
+    #[msg("Amount exceeds the max borrowable value of the collateral")]
+    BorrowExceedsMaxBorrowable,
+    #[msg("Collateral not deposited, cannot borrow")]
+    CollateralNotDeposited,
+    #[msg("Loan already drawn, use draw additional to borrow more")]
+    LoanAlreadyDrawn,
+    #[msg("Loan not drawn yet, nothing to draw additionally on")]
+    LoanNotDrawn,
+    #[msg("Loan is not active")]
+    LoanNotActive,
+    #[msg("Borrow would push pool utilization above the max utilization")]
+    MaxUtilizationExceeded,
+    #[msg("Invalid pool configuration")]
+    InvalidPoolConfig,
+    #[msg("Invalid rate type, must be fixed (0) or variable (1)")]
+    InvalidRateType,
+    #[msg("Invalid rate model parameters")]
+    InvalidRateModel,
+    #[msg("Loan term must be greater than zero")]
+    InvalidLoanTerm,
+    #[msg("Loan has matured, cannot draw more")]
+    LoanMatured,
+    #[msg("Loan cannot be marked defaulted before maturity + grace period or the missed installment limit, or when it isn't active")]
+    CannotMarkDefaulted,
+    #[msg("Invalid repayment type, must be bullet (0) or installments (1)")]
+    InvalidRepaymentType,
+    #[msg("Invalid origination fee mode, must be capitalize (0) or deduct (1)")]
+    InvalidOriginationFeeMode,
+    #[msg("Loan doesn't have an installment schedule")]
+    NotAnInstallmentLoan,
+    #[msg("All installments are already paid")]
+    NoInstallmentDue,
+    #[msg("Cannot draw more on an installment loan")]
+    CannotDrawOnInstallmentLoan,
+    #[msg("Cannot refinance a loan with missed installments or past its grace period")]
+    CannotRefinance,
+    #[msg("Not enough protocol fees to withdraw")]
+    InsufficientProtocolFees,
+    #[msg("A flash loan is already active")]
+    FlashLoanActive,
+    #[msg("No active flash loan to repay")]
+    FlashLoanNotActive,
+    #[msg("Flash borrow must be followed by a flash repay in the same transaction")]
+    FlashRepayMissing,
+    #[msg("Flash borrow cannot be called through a CPI")]
+    FlashLoanCpiNotAllowed,
+    #[msg("Verification request cannot move to that status")]
+    InvalidVerificationTransition,
+    #[msg("Collateral is already deposited in the pool")]
+    CollateralAlreadyDeposited,
+    #[msg("Verification can only be cancelled while pending or once the review SLA has passed")]
+    CannotCancelVerification,
+    #[msg("This admin already attested to the verification")]
+    AlreadyAttested,
+    #[msg("Verification has the maximum number of attestations")]
+    MaxAttestations,
+    #[msg("Assay report is missing fields, too long or dated in the future")]
+    InvalidAssayReport,
+    #[msg("Interest mode cannot change while loans are outstanding")]
+    LoansOutstanding,
+    #[msg("Assayed weight must be positive and purity at most 10000 bps")]
+    InvalidAssayResult,
+    #[msg("Verification quorum can't exceed the number of admins")]
+    QuorumUnreachable,
 }
@@ -0,0 +1,30 @@
+use crate::states::AssayReport;
+use anchor_lang::prelude::*;
+
+//e An admin attested to a verification request, with the assay report it's based on
+#[event]
+pub struct AttestationSubmitted {
+    pub lending_pool: Pubkey,
+    pub verification_request: Pubkey,
+    pub verification_id: u32,
+    pub verifier: Pubkey,
+    pub approve: bool,
+    pub weight_in_grams: i64,
+    pub purity_in_bps: u16,
+    pub conflicting: bool,
+    pub report: AssayReport,
+    pub attested_at: i64,
+}
+
+//e The attestations reached the quorum and the request was approved or rejected
+#[event]
+pub struct VerificationDecided {
+    pub lending_pool: Pubkey,
+    pub verification_request: Pubkey,
+    pub verification_id: u32,
+    pub approved: bool,
+    pub weight_in_grams: i64,
+    pub purity_in_bps: u16,
+    pub report: AssayReport,
+    pub decided_at: i64,
+}
@@ -0,0 +1,43 @@
+use crate::states::{LendingPool, RateModel};
+use anchor_lang::prelude::*;
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Permissionless checkpoint, anyone can move the borrow index forward so the LP exchange rate reflects accrued interest
+#[derive(Accounts)]
+pub struct AccrueInterest<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
+}
+
+impl<'info> AccrueInterest<'info> {
+    pub fn accrue_interest(&mut self) -> Result<()> {
+        self.lending_pool
+            .accrue_interest(&self.rate_model, Clock::get()?.unix_timestamp)?;
+
+        msg!(
+            "Borrow index: {}, Total interest accrued: {}",
+            self.lending_pool.borrow_index,
+            self.lending_pool.total_interest_accrued
+        );
+        Ok(())
+    }
+}
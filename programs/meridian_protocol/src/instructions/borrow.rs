@@ -1,8 +1,15 @@
 use std::ops::Mul;
 
-use crate::constants::{GOLD_USD_PRICE_FEED, MAX_AGE};
+use crate::constants::{
+    GOLD_USD_PRICE_FEED, MAX_AGE, ORIGINATION_FEE_MODE_DEDUCT, RATE_TYPE_FIXED,
+    RATE_TYPE_VARIABLE, REPAYMENT_TYPE_BULLET, REPAYMENT_TYPE_INSTALLMENT,
+};
 use crate::errors::Errors;
-use crate::states::{LendingPool, LoanState, MockOracleState};
+use crate::math::{mul_div, to_u64, BPS};
+use crate::states::{
+    BorrowerProfile, LendingPool, LoanState, MockOracleState, RateModel, VerificationRequest,
+    VerificationStatus,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::Token;
@@ -20,33 +27,63 @@ use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2
 
 #[derive(Accounts)]
 pub struct Borrow<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
     #[account(mut)]
     pub borrower: Signer<'info>,
     #[account(mut)]
     pub mint_usdc: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",lending_pool.owner.as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
     #[account(
         mut,
         associated_token::mint = mint_usdc,
-        associated_token::authority = lending_pool.owner,
+        associated_token::authority = lending_pool,
         associated_token::token_program = token_program,
     )]
     pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-        init_if_needed,
-        payer = borrower,
-        space = 8 + LoanState::INIT_SPACE,
-        seeds = [b"meridian_borrower_state", borrower.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"meridian_fee_vault", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_fee_vault
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"meridian_borrower_profile", lending_pool.key().as_ref(), borrower.key().as_ref()],
+        bump = borrower_profile.bump
+    )]
+    pub borrower_profile: Box<Account<'info, BorrowerProfile>>,
+    #[account(
+        mut,
+        has_one = borrower,
+        seeds = [
+            b"meridian_borrower_state",
+            lending_pool.key().as_ref(),
+            borrower.key().as_ref(),
+            &borrower_state.loan_id.to_le_bytes()
+        ],
+        bump = borrower_state.bump_borrower_state
     )]
     pub borrower_state: Box<Account<'info, LoanState>>,
+    #[account(
+        mut,
+        seeds = [b"meridian_verification_request", borrower_state.key().as_ref()],
+        bump = verification_request.bump
+    )]
+    pub verification_request: Box<Account<'info, VerificationRequest>>,
     #[account(
         init_if_needed,
         payer = borrower,
@@ -55,8 +92,8 @@ pub struct Borrow<'info> {
         associated_token::token_program = token_program,
     )]
     pub borrower_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
-    ///CHECK: Safe,Will be created on the client side
-    #[account(mut)]
+    ///CHECK: The asset pledged to this loan
+    #[account(mut, address = borrower_state.nft_mint)]
     pub rwa_asset: UncheckedAccount<'info>,
     ///CHECK:
     #[account(
@@ -80,41 +117,12 @@ pub struct Borrow<'info> {
 }
 
 impl<'info> Borrow<'info> {
-    pub fn deposit_for_verification(&mut self, bumps: &BorrowBumps) -> Result<()> {
-        require!(
-            self.borrower_state.is_sent_for_verification == false,
-            Errors::AssetAlreadySentForVerification
-        );
-        require!(self.lending_pool.is_locked == false, Errors::PoolLocked);
-
-        TransferV1CpiBuilder::new(&self.mpl_core_program.to_account_info())
-            .payer(&self.borrower.to_account_info())
-            .asset(&self.rwa_asset.to_account_info())
-            .authority(Some(&self.borrower.to_account_info()))
-            .new_owner(&self.protocol_verification_vault)
-            .invoke()?;
-
-        self.borrower_state.verification_id += 1;
-        self.borrower_state.bump_borrower_state = bumps.borrower_state;
-        self.borrower_state.borrower = self.borrower.key();
-
-        msg!(
-            "Asset transferred for verification: {}",
-            &self.rwa_asset.key()
-        );
-        msg!(
-            "Your borrower verification id is: {}",
-            self.borrower_state.verification_id
-        );
-        Ok(())
-    }
-
     pub fn get_verification_id(&mut self) -> Result<u32> {
         return Ok(self.borrower_state.verification_id);
     }
 
     pub fn is_asset_verified(&mut self) -> bool {
-        if self.borrower_state.is_verified {
+        if self.verification_request.is_approved() {
             println!("Your asset is verified");
             return true;
         } else {
@@ -124,22 +132,240 @@ impl<'info> Borrow<'info> {
     }
 
     //e WE'RE USING MOCK ORACLE AS FOR NOW...
-    pub fn borrow(&mut self, use_pyth: bool) -> Result<()> {
-        require!(self.lending_pool.is_locked == false, Errors::PoolLocked);
+    pub fn borrow(
+        &mut self,
+        amount: u64,
+        rate_type: u8,
+        term_seconds: i64,
+        repayment_type: u8,
+        origination_fee_mode: Option<u8>,
+    ) -> Result<()> {
+        require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
         require!(self.is_asset_verified(), Errors::AssetNotVerified);
+        require!(
+            self.borrower_state.current_owner_asset == self.lending_pool.key(),
+            Errors::CollateralNotDeposited
+        );
+        require!(
+            self.borrower_state.principal_borrowed == 0 && self.borrower_state.loan_status == 0,
+            Errors::LoanAlreadyDrawn
+        );
+        require!(term_seconds > 0, Errors::InvalidLoanTerm);
+        require!(
+            repayment_type == REPAYMENT_TYPE_BULLET || repayment_type == REPAYMENT_TYPE_INSTALLMENT,
+            Errors::InvalidRepaymentType
+        );
+        //e Borrower's pick, otherwise the pool default. Later drawdowns follow the same mode
+        let origination_fee_mode =
+            origination_fee_mode.unwrap_or(self.lending_pool.origination_fee_mode);
+        require!(
+            origination_fee_mode <= ORIGINATION_FEE_MODE_DEDUCT,
+            Errors::InvalidOriginationFeeMode
+        );
+        self.borrower_state.origination_fee_mode = origination_fee_mode;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        self.lending_pool
+            .accrue_interest(&self.rate_model, current_time)?;
+        self.borrower_state.borrowed_at = current_time;
+        self.borrower_state.term_seconds = term_seconds;
+        self.borrower_state.maturity_ts = current_time
+            .checked_add(term_seconds)
+            .ok_or(error!(Errors::MathOverflow))?;
+        self.borrower_state.last_interest_accrued = current_time;
+        self.borrower_state.borrow_index_snapshot = self.lending_pool.borrow_index;
+        self.borrower_state.variable_index_snapshot = self.lending_pool.variable_rate_index;
+        self.borrower_state.reserve_index_snapshot = self.lending_pool.reserve_index;
+        self.borrower_state.reserve_variable_index_snapshot =
+            self.lending_pool.reserve_variable_index;
+
+        //e Rate is priced off the utilization before this loan is drawn. Locking it in costs the fixed rate premium
+        require!(
+            rate_type == RATE_TYPE_FIXED || rate_type == RATE_TYPE_VARIABLE,
+            Errors::InvalidRateType
+        );
+        let current_rate = self.calculate_borrow_rate_tier()?;
+        self.borrower_state.rate_type = rate_type;
+        self.borrower_state.borrow_apr_bps = if rate_type == RATE_TYPE_FIXED {
+            current_rate.saturating_add(self.lending_pool.fixed_rate_premium_bps)
+        } else {
+            current_rate
+        };
 
-        self.borrower_state.last_interest_accrued = Clock::get()?.unix_timestamp;
-
-        self.borrow_assets(use_pyth)?;
+        self.borrow_assets(amount)?;
         self.borrower_state.loan_status = 0;
+
+        //e Installments are sized off the debt and rate at origination
+        if repayment_type == REPAYMENT_TYPE_INSTALLMENT {
+            self.borrower_state.set_installment_schedule()?;
+            msg!(
+                "{} installments of {}",
+                self.borrower_state.installment_count,
+                self.borrower_state.installment_amount
+            );
+        }
+        Ok(())
+    }
+
+    //e Draw more against the same collateral, e.g. once gold has appreciated. Fixed loans keep the rate of the first drawdown
+    pub fn draw_additional(&mut self, amount: u64) -> Result<()> {
+        require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        require!(self.borrower_state.loan_status == 0, Errors::LoanNotActive);
+        require!(
+            self.borrower_state.principal_borrowed > 0,
+            Errors::LoanNotDrawn
+        );
+        require!(
+            !self.borrower_state.is_matured(Clock::get()?.unix_timestamp),
+            Errors::LoanMatured
+        );
+        //e The schedule was sized at origination, more principal would leave it short
+        require!(
+            self.borrower_state.repayment_type == REPAYMENT_TYPE_BULLET,
+            Errors::CannotDrawOnInstallmentLoan
+        );
+
+        //e Book interest on the current principal so the new drawdown only accrues from now
+        self.lending_pool
+            .settle_loan(
+                &self.rate_model,
+                &mut self.borrower_state,
+                Clock::get()?.unix_timestamp,
+            )?;
+
+        self.borrow_assets(amount)
+    }
+
+    //e Rolls an open loan into new terms at today's rate and collateral value, the collateral stays where it is.
+    //e Outstanding interest is capitalized into the principal and the term/schedule restart from now
+    pub fn refinance(
+        &mut self,
+        rate_type: u8,
+        term_seconds: i64,
+        repayment_type: u8,
+    ) -> Result<()> {
+        require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        require!(self.borrower_state.loan_status == 0, Errors::LoanNotActive);
+        require!(
+            self.borrower_state.principal_borrowed > 0,
+            Errors::LoanNotDrawn
+        );
+        require!(term_seconds > 0, Errors::InvalidLoanTerm);
+        require!(
+            rate_type == RATE_TYPE_FIXED || rate_type == RATE_TYPE_VARIABLE,
+            Errors::InvalidRateType
+        );
+        require!(
+            repayment_type == REPAYMENT_TYPE_BULLET || repayment_type == REPAYMENT_TYPE_INSTALLMENT,
+            Errors::InvalidRepaymentType
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        self.lending_pool
+            .settle_loan(&self.rate_model, &mut self.borrower_state, current_time)?;
+        let late_fee_bps = self.lending_pool.late_fee_bps;
+        self.borrower_state
+            .update_missed_installments(late_fee_bps, current_time)?;
+        //e Missed installments have to be caught up first and a loan past its grace period is already defaultable,
+        //e refinancing can't be used to wipe either
+        let past_grace_period = self.borrower_state.maturity_ts > 0
+            && current_time > self.borrower_state.maturity_ts + self.lending_pool.grace_period;
+        require!(
+            self.borrower_state.missed_installments == 0 && !past_grace_period,
+            Errors::CannotRefinance
+        );
+
+        let refinance_fee = to_u64(mul_div(
+            self.borrower_state.base_debt() as u128,
+            self.lending_pool.refinance_fee_bps as u128,
+            BPS,
+        )?)?;
+
+        //e LTV is checked against the collateral value right now
+        let borrowable_value = self.calculate_borrowable_value_of_the_asset_mock_oracle()?;
+        require!(
+            self.borrower_state.base_debt() + refinance_fee <= borrowable_value,
+            Errors::BorrowExceedsMaxBorrowable
+        );
+
+        self.lending_pool.remove_loan_weight(&self.borrower_state);
+
+        //e The LP part of the interest owed becomes principal, the reserve cut stays owed to the protocol
+        //e so pool assets don't change and the reserve is still paid out on repayment
+        let interest = self.borrower_state.interest_accrued;
+        let reserve = self.borrower_state.reserve_accrued.min(interest);
+        let capitalized_interest = interest - reserve;
+        self.borrower_state.principal_borrowed += capitalized_interest;
+        self.borrower_state.interest_accrued = 0;
+        self.borrower_state.pending_interest = 0;
+        self.borrower_state.reserve_owed += reserve;
+        self.borrower_state.reserve_accrued = 0;
+        self.borrower_state.origination_fee += refinance_fee;
+        self.lending_pool.total_borrowed += capitalized_interest;
+        self.lending_pool.release_interest(interest);
+        self.lending_pool.release_reserves(reserve);
+
+        //e New rate off current utilization
+        let current_rate = self.calculate_borrow_rate_tier()?;
+        self.borrower_state.rate_type = rate_type;
+        self.borrower_state.borrow_apr_bps = if rate_type == RATE_TYPE_FIXED {
+            current_rate.saturating_add(self.lending_pool.fixed_rate_premium_bps)
+        } else {
+            current_rate
+        };
+
+        //e New term starting now
+        self.borrower_state.borrowed_at = current_time;
+        self.borrower_state.term_seconds = term_seconds;
+        self.borrower_state.maturity_ts = current_time
+            .checked_add(term_seconds)
+            .ok_or(error!(Errors::MathOverflow))?;
+        self.borrower_state.repayment_type = REPAYMENT_TYPE_BULLET;
+        self.borrower_state.installment_count = 0;
+        self.borrower_state.installment_amount = 0;
+        self.borrower_state.installments_paid = 0;
+        self.borrower_state.schedule_repaid = 0;
+        self.borrower_state.late_fees_charged_upto = 0;
+        if repayment_type == REPAYMENT_TYPE_INSTALLMENT {
+            self.borrower_state.set_installment_schedule()?;
+        }
+
+        self.lending_pool.add_loan_weight(&self.borrower_state)?;
+        self.borrower_state.collateral_value_usd =
+            self.calculate_value_of_the_asset_mock_oracle()?;
+
+        msg!(
+            "Loan refinanced, principal: {}, apr: {} bps, maturity: {}, refinance fee: {}",
+            self.borrower_state.principal_borrowed,
+            self.borrower_state.borrow_apr_bps,
+            self.borrower_state.maturity_ts,
+            refinance_fee
+        );
         Ok(())
     }
 
     pub fn deposit_collateral(&mut self) -> Result<()> {
         require!(
-            self.borrower_state.is_verified == true,
+            self.verification_request.is_approved(),
             Errors::AssetNotVerified
         );
+        //e Approved assets sit in the verification vault until they're deposited, once
+        require!(
+            self.borrower_state.current_owner_asset != self.lending_pool.key(),
+            Errors::CollateralAlreadyDeposited
+        );
 
         let key = self.lending_pool.key();
         let bump = &[self.lending_pool.bump_verification_vault];
@@ -163,10 +389,31 @@ impl<'info> Borrow<'info> {
     //e Borrower can collect their collateral back if the collateral is rejected by the admin
     pub fn collect_collateral(&mut self) -> Result<()> {
         require!(
-            self.borrower_state.is_verified == false && self.borrower_state.is_rejected,
+            self.verification_request.status == VerificationStatus::Rejected,
             Errors::CannotCollectCollateral
         );
 
+        self.return_asset_to_borrower()?;
+        msg!("Collateral Collected Back: {}", self.rwa_asset.key());
+        Ok(())
+    }
+
+    //e Borrower takes the asset back before any decision, while pending or when admins blew the review SLA
+    pub fn cancel_verification(&mut self) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(
+            self.verification_request.can_cancel(current_time),
+            Errors::CannotCancelVerification
+        );
+
+        self.verification_request
+            .transition(VerificationStatus::Cancelled, current_time)?;
+        self.return_asset_to_borrower()?;
+        msg!("Verification cancelled, asset returned: {}", self.rwa_asset.key());
+        Ok(())
+    }
+
+    fn return_asset_to_borrower(&mut self) -> Result<()> {
         let key = self.lending_pool.key();
         let bump = &[self.lending_pool.bump_verification_vault];
         let signer_seeds: &[&[u8]] = &[b"meridian_verification_vault", key.as_ref(), bump];
@@ -180,32 +427,47 @@ impl<'info> Borrow<'info> {
             .authority(Some(&self.protocol_verification_vault.to_account_info()))
             .invoke_signed(seeds)?;
 
-        msg!("Collateral Collected Back: {}", self.rwa_asset.key());
-
+        self.borrower_state.current_owner_asset = self.borrower.key();
+        self.borrower_profile.open_loans -= 1;
+        self.verification_request
+            .transition(VerificationStatus::Returned, Clock::get()?.unix_timestamp)?;
         Ok(())
     }
 
-    fn borrow_assets(&mut self, use_pyth: bool) -> Result<()> {
-        let borrowable_value: u64;
-        // if use_pyth {
-        //     borrowable_value = self.calculate_borrowable_value_of_the_asset_pyth()?;
-        // } else {
-        borrowable_value = self.calculate_borrowable_value_of_the_asset_mock_oracle()?;
-        // }
+    fn borrow_assets(&mut self, amount: u64) -> Result<()> {
+        require!(amount > 0, Errors::NullBorrowNotAllowed);
 
-        let origination_fee = self.calculate_origination_fee(borrowable_value)?;
+        //e Valued off the mock oracle, the pyth version below is still commented out
+        let borrowable_value = self.calculate_borrowable_value_of_the_asset_mock_oracle()?;
 
-        // require!(
-        //     self.lending_pool.total_deposited_usdc >= borrowable_value,
-        //     Errors::InsufficientLiquidityToBorrow
-        // );
+        //e Whatever is already owed on this loan eats into the LTV headroom
+        let max_borrowable = borrowable_value.saturating_sub(self.borrower_state.base_debt());
+        require!(
+            amount <= max_borrowable,
+            Errors::BorrowExceedsMaxBorrowable
+        );
+
+        let origination_fee = self.calculate_origination_fee(amount)?;
+        let deduct_fee = self.borrower_state.origination_fee_mode == ORIGINATION_FEE_MODE_DEDUCT;
+        //e Net disbursement, the borrower still owes the full amount
+        let amount_to_borrower = if deduct_fee {
+            amount - origination_fee
+        } else {
+            amount
+        };
+
+        self.validate_pool_liquidity(amount)?;
 
         let owner = self.lending_pool.owner.key();
+        let usdc_mint = self.lending_pool.usdc_mint.key();
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
 
         //e Signer seeds
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"meridian_pool",
             owner.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
             &[self.lending_pool.bump_lending_pool],
         ]];
 
@@ -213,22 +475,59 @@ impl<'info> Borrow<'info> {
             mint: self.mint_usdc.to_account_info(),
             from: self.lending_pool_usdc_ata.to_account_info(),
             to: self.borrower_usdc_ata.to_account_info(),
-            authority: self.authority.to_account_info(),
+            authority: self.lending_pool.to_account_info(),
         };
 
         let program = self.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
-        transfer_checked(cpi_ctx, borrowable_value, self.mint_usdc.decimals)?;
-        msg!("Transferred value: {}", borrowable_value);
-        self.borrower_state.principal_borrowed = borrowable_value;
-        self.borrower_state.origination_fee += origination_fee;
-        self.lending_pool.total_borrowed += borrowable_value;
-        self.borrower_state.borrow_apr_bps = self.calculate_borrow_rate_tier()?;
+        let cpi_ctx = CpiContext::new_with_signer(program.clone(), accounts, signer_seeds);
+        transfer_checked(cpi_ctx, amount_to_borrower, self.mint_usdc.decimals)?;
+        msg!("Transferred value: {}", amount_to_borrower);
+
+        //e Origination fee is charged on every drawdown, either paid now out of the disbursement or added to the debt
+        if deduct_fee {
+            if origination_fee > 0 {
+                let accounts = TransferChecked {
+                    mint: self.mint_usdc.to_account_info(),
+                    from: self.lending_pool_usdc_ata.to_account_info(),
+                    to: self.protocol_fee_vault.to_account_info(),
+                    authority: self.lending_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(program, accounts, signer_seeds);
+                transfer_checked(cpi_ctx, origination_fee, self.mint_usdc.decimals)?;
+                self.lending_pool.origination_fees_collected += origination_fee;
+            }
+        } else {
+            self.borrower_state.origination_fee += origination_fee;
+        }
+        self.lending_pool.remove_loan_weight(&self.borrower_state);
+        self.borrower_state.principal_borrowed += amount;
+        self.lending_pool.add_loan_weight(&self.borrower_state)?;
+        self.lending_pool.total_borrowed += amount;
+        self.lending_pool.total_idle_usdc -= amount;
         self.borrower_state.collateral_value_usd =
             self.calculate_value_of_the_asset_mock_oracle()?;
         Ok(())
     }
 
+    //e Borrows come out of actual vault cash, minus what's reserved for withdrawals, and can't push utilization past the cap
+    fn validate_pool_liquidity(&mut self, amount: u64) -> Result<()> {
+        let borrowable_liquidity = self
+            .lending_pool
+            .borrowable_liquidity()?
+            .min(self.lending_pool_usdc_ata.amount);
+        require!(
+            amount <= borrowable_liquidity,
+            Errors::InsufficientLiquidityToBorrow
+        );
+
+        let utilization_after_borrow = self.lending_pool.utilization_after_borrow_bps(amount)?;
+        require!(
+            utilization_after_borrow <= self.lending_pool.max_utilization_bps as u64,
+            Errors::MaxUtilizationExceeded
+        );
+        Ok(())
+    }
+
     pub fn calculate_value_of_the_asset_mock_oracle(&mut self) -> Result<u64> {
         let mock_oracle = &mut self.mock_oracle;
 
@@ -252,54 +551,21 @@ impl<'info> Borrow<'info> {
     }
 
     pub fn calculate_borrow_rate_tier(&mut self) -> Result<u16> {
-        //e Retrieving current utlization rate...
-        let current_utilization_rate = self.get_current_utilization_rate()?;
-        let current_utilization_rate_bps = current_utilization_rate as u16;
-        // let current_utilization_rate_bps =
-        //     current_utilization_rate.checked_div(10_000).unwrap() as u16;
-        let u1_bps = self.lending_pool.utilization_rate_tier_1_bps; //0 to 2500
-        let u2_bps = self.lending_pool.utilization_rate_tier_2_bps; //2500 to //5000
-        let u3_bps = self.lending_pool.utilization_rate_tier_3_bps; //5000 to 7500
-        let u4_bps = self.lending_pool.utilization_rate_tier_4_bps; //7500 to //9000
-        let u5_bps = self.lending_pool.utilization_rate_tier_5_bps; //9000+
-
-        let current_borrow_apr_rate_bps: u16;
-
-        if current_utilization_rate_bps >= u1_bps && current_utilization_rate_bps < u2_bps {
-            current_borrow_apr_rate_bps = self.lending_pool.apr_tier_1_bps;
-        } else if current_utilization_rate_bps >= u2_bps && current_utilization_rate_bps < u3_bps {
-            current_borrow_apr_rate_bps = self.lending_pool.apr_tier_2_bps;
-        } else if current_utilization_rate_bps >= u3_bps && current_utilization_rate_bps < u4_bps {
-            current_borrow_apr_rate_bps = self.lending_pool.apr_tier_3_bps;
-        } else if current_utilization_rate_bps >= u4_bps && current_utilization_rate_bps < u5_bps {
-            current_borrow_apr_rate_bps = self.lending_pool.apr_tier_4_bps;
-        } else {
-            current_borrow_apr_rate_bps = self.lending_pool.apr_tier_5_bps
-        }
-
-        Ok(current_borrow_apr_rate_bps)
+        //e Priced by the pool's rate model off the current utilization
+        self.lending_pool.current_borrow_rate_bps(&self.rate_model)
     }
 
-    //e Total_Borrowed * 10_000/Total_Deposited..
+    //e Outstanding debt * 10_000 / Total pool assets..
     pub fn get_current_utilization_rate(&mut self) -> Result<u64> {
-        let total_borrowed = self.lending_pool.total_borrowed;
-        let total_deposited = self.lending_pool.total_deposited_usdc;
-
-        if total_deposited == 0 {
-            return Ok(0);
-        }
-
-        let utilization_rate = total_borrowed
-            .checked_mul(10_000)
-            .unwrap()
-            .checked_div(total_deposited)
-            .unwrap();
-
-        Ok(utilization_rate)
+        self.lending_pool.utilization_rate_bps()
     }
 
     pub fn calculate_origination_fee(&mut self, total_value_borrowed: u64) -> Result<u64> {
-        return Ok(total_value_borrowed * self.lending_pool.origination_fee_bps as u64 / 10_000);
+        to_u64(mul_div(
+            total_value_borrowed as u128,
+            self.lending_pool.origination_fee_bps as u128,
+            BPS,
+        )?)
     }
 
     pub fn calculate_borrowable_value_of_the_asset_mock_oracle(&mut self) -> Result<u64> {
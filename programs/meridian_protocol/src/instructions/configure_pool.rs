@@ -0,0 +1,283 @@
+use crate::constants::{
+    INTEREST_MODE_COMPOUND_PERIODIC, MAX_ATTESTATIONS, MAX_RATE_POINTS, ORIGINATION_FEE_MODE_DEDUCT,
+    RATE_MODEL_KINKED, RATE_MODEL_PIECEWISE_LINEAR,
+};
+use crate::errors::Errors;
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+#[derive(Accounts)]
+pub struct ConfigurePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        mut,
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
+    #[account(
+        seeds = [b"meridian_pool_admin_registry", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_admin_registry
+    )]
+    pub admin_registry: Box<Account<'info, AdminRegistry>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ConfigurePool<'info> {
+    pub fn set_borrow_limits(
+        &mut self,
+        reserve_ratio_bps: u16,
+        max_utilization_bps: u16,
+    ) -> Result<()> {
+        self.only_owner()?;
+        require!(
+            reserve_ratio_bps <= 10_000 && max_utilization_bps <= 10_000,
+            Errors::InvalidPoolConfig
+        );
+
+        self.lending_pool.reserve_ratio_bps = reserve_ratio_bps;
+        self.lending_pool.max_utilization_bps = max_utilization_bps;
+        msg!(
+            "Borrow limits updated, reserve ratio: {} bps, max utilization: {} bps",
+            reserve_ratio_bps,
+            max_utilization_bps
+        );
+        Ok(())
+    }
+
+    //e Applies from the next accrual, interest booked so far is kept as is
+    pub fn set_interest_mode(&mut self, interest_mode: u8, compounding_period: i64) -> Result<()> {
+        self.only_owner()?;
+        require!(
+            interest_mode <= INTEREST_MODE_COMPOUND_PERIODIC,
+            Errors::InvalidPoolConfig
+        );
+        require!(
+            interest_mode != INTEREST_MODE_COMPOUND_PERIODIC || compounding_period > 0,
+            Errors::InvalidPoolConfig
+        );
+        //e Loan weights in the pool sums are taken under the current mode, switching it would leave them stale
+        require!(
+            self.lending_pool.total_borrowed == 0
+                && self.lending_pool.total_weighted_debt == 0
+                && self.lending_pool.total_variable_debt == 0,
+            Errors::LoansOutstanding
+        );
+
+        self.lending_pool
+            .accrue_interest(&self.rate_model, Clock::get()?.unix_timestamp)?;
+
+        self.lending_pool.interest_mode = interest_mode;
+        self.lending_pool.compounding_period = compounding_period;
+        msg!(
+            "Interest mode updated: {}, compounding period: {}s",
+            interest_mode,
+            compounding_period
+        );
+        Ok(())
+    }
+
+    //e Only priced into loans originated from now on, existing fixed loans keep their rate
+    pub fn set_fixed_rate_premium(&mut self, fixed_rate_premium_bps: u16) -> Result<()> {
+        self.only_owner()?;
+        require!(fixed_rate_premium_bps <= 10_000, Errors::InvalidPoolConfig);
+
+        self.lending_pool.fixed_rate_premium_bps = fixed_rate_premium_bps;
+        msg!("Fixed rate premium updated: {} bps", fixed_rate_premium_bps);
+        Ok(())
+    }
+
+    //e Interest up to now is accrued at the old curve before switching
+    pub fn set_rate_model(
+        &mut self,
+        model_type: u8,
+        base_rate_bps: u16,
+        slope_1_bps: u16,
+        slope_2_bps: u16,
+        optimal_utilization_bps: u16,
+        points: Vec<RatePoint>,
+    ) -> Result<()> {
+        self.only_owner()?;
+        require!(
+            model_type <= RATE_MODEL_PIECEWISE_LINEAR,
+            Errors::InvalidRateModel
+        );
+        if model_type == RATE_MODEL_KINKED {
+            require!(
+                optimal_utilization_bps > 0 && optimal_utilization_bps <= 10_000,
+                Errors::InvalidRateModel
+            );
+            //e The kinked curve is fully described by its slopes, it takes no points
+            require!(points.is_empty(), Errors::InvalidRateModel);
+        } else {
+            RateModel::validate_points(&points)?;
+        }
+
+        self.lending_pool
+            .accrue_interest(&self.rate_model, Clock::get()?.unix_timestamp)?;
+
+        let rate_model = &mut self.rate_model;
+        rate_model.model_type = model_type;
+        rate_model.base_rate_bps = base_rate_bps;
+        rate_model.slope_1_bps = slope_1_bps;
+        rate_model.slope_2_bps = slope_2_bps;
+        rate_model.optimal_utilization_bps = optimal_utilization_bps;
+        rate_model.points = [RatePoint::default(); MAX_RATE_POINTS];
+        for (i, point) in points.iter().enumerate() {
+            rate_model.points[i] = *point;
+        }
+        rate_model.point_count = points.len() as u8;
+
+        msg!("Rate model updated, type: {}", model_type);
+        Ok(())
+    }
+
+    //e The penalty APR applies to overdue time from the next settle on
+    pub fn set_term_params(&mut self, grace_period: i64, penalty_apr_bps: u16) -> Result<()> {
+        self.only_owner()?;
+        require!(grace_period >= 0, Errors::InvalidPoolConfig);
+
+        self.lending_pool.grace_period = grace_period;
+        self.lending_pool.penalty_apr_bps = penalty_apr_bps;
+        msg!(
+            "Term params updated, grace period: {}s, penalty apr: {} bps",
+            grace_period,
+            penalty_apr_bps
+        );
+        Ok(())
+    }
+
+    pub fn set_installment_params(
+        &mut self,
+        late_fee_bps: u16,
+        max_missed_installments: u16,
+    ) -> Result<()> {
+        self.only_owner()?;
+        require!(
+            late_fee_bps <= 10_000 && max_missed_installments > 0,
+            Errors::InvalidPoolConfig
+        );
+
+        self.lending_pool.late_fee_bps = late_fee_bps;
+        self.lending_pool.max_missed_installments = max_missed_installments;
+        msg!(
+            "Installment params updated, late fee: {} bps, max missed installments: {}",
+            late_fee_bps,
+            max_missed_installments
+        );
+        Ok(())
+    }
+
+    pub fn set_refinance_fee(&mut self, refinance_fee_bps: u16) -> Result<()> {
+        self.only_owner()?;
+        require!(refinance_fee_bps <= 10_000, Errors::InvalidPoolConfig);
+
+        self.lending_pool.refinance_fee_bps = refinance_fee_bps;
+        msg!("Refinance fee updated: {} bps", refinance_fee_bps);
+        Ok(())
+    }
+
+    //e Only applies to verification requests made from now on
+    pub fn set_verification_sla(&mut self, verification_sla: i64) -> Result<()> {
+        self.only_owner()?;
+        require!(verification_sla > 0, Errors::InvalidPoolConfig);
+
+        self.lending_pool.verification_sla = verification_sla;
+        msg!("Verification SLA updated: {} seconds", verification_sla);
+        Ok(())
+    }
+
+    //e M of N, applies to every verification that isn't decided yet
+    pub fn set_verification_quorum(
+        &mut self,
+        verification_quorum: u8,
+        attestation_tolerance_bps: u16,
+    ) -> Result<()> {
+        self.only_owner()?;
+        require!(
+            verification_quorum >= 1 && verification_quorum as usize <= MAX_ATTESTATIONS,
+            Errors::InvalidPoolConfig
+        );
+        require!(attestation_tolerance_bps <= 10_000, Errors::InvalidPoolConfig);
+        //e A quorum above the admin count could never be met and every verification would hang
+        require!(
+            verification_quorum as usize <= self.admin_registry.admins.len(),
+            Errors::QuorumUnreachable
+        );
+
+        self.lending_pool.verification_quorum = verification_quorum;
+        self.lending_pool.attestation_tolerance_bps = attestation_tolerance_bps;
+        msg!(
+            "Verification quorum updated: {} attestations within {} bps",
+            verification_quorum,
+            attestation_tolerance_bps
+        );
+        Ok(())
+    }
+
+    pub fn set_flash_fee(&mut self, flash_fee_bps: u16) -> Result<()> {
+        self.only_owner()?;
+        require!(flash_fee_bps <= 10_000, Errors::InvalidPoolConfig);
+
+        self.lending_pool.flash_fee_bps = flash_fee_bps;
+        msg!("Flash fee updated: {} bps", flash_fee_bps);
+        Ok(())
+    }
+
+    //e Default for loans opened from now on, borrowers can still pick the other mode at borrow
+    pub fn set_origination_fee_mode(&mut self, origination_fee_mode: u8) -> Result<()> {
+        self.only_owner()?;
+        require!(
+            origination_fee_mode <= ORIGINATION_FEE_MODE_DEDUCT,
+            Errors::InvalidOriginationFeeMode
+        );
+
+        self.lending_pool.origination_fee_mode = origination_fee_mode;
+        msg!("Origination fee mode updated: {}", origination_fee_mode);
+        Ok(())
+    }
+
+    //e Only applies to interest accrued from now on, the reserve of what accrued so far is already booked
+    pub fn set_reserve_factor(&mut self, reserve_factor_bps: u16) -> Result<()> {
+        self.only_owner()?;
+        require!(reserve_factor_bps <= 10_000, Errors::InvalidPoolConfig);
+
+        self.lending_pool
+            .accrue_interest(&self.rate_model, Clock::get()?.unix_timestamp)?;
+
+        self.lending_pool.reserve_factor_bps = reserve_factor_bps;
+        msg!("Reserve factor updated: {} bps", reserve_factor_bps);
+        Ok(())
+    }
+
+    fn only_owner(&self) -> Result<()> {
+        require!(
+            self.authority.key() == self.lending_pool.owner.key(),
+            Errors::OnlyAuthority
+        );
+        //e Pool params can't move under an open flash loan
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        Ok(())
+    }
+}
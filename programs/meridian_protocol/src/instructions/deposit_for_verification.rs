@@ -0,0 +1,123 @@
+use crate::errors::Errors;
+use crate::states::{
+    BorrowerProfile, LendingPool, LoanState, VerificationRequest, VerificationStatus,
+};
+use anchor_lang::prelude::*;
+use mpl_core::instructions::TransferV1CpiBuilder;
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Opens a new loan for the pledged asset, every asset gets its own loan account
+#[derive(Accounts)]
+pub struct DepositForVerification<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        init_if_needed,
+        payer = borrower,
+        space = 8 + BorrowerProfile::INIT_SPACE,
+        seeds = [b"meridian_borrower_profile", lending_pool.key().as_ref(), borrower.key().as_ref()],
+        bump
+    )]
+    pub borrower_profile: Box<Account<'info, BorrowerProfile>>,
+    #[account(
+        init,
+        payer = borrower,
+        space = 8 + LoanState::INIT_SPACE,
+        seeds = [
+            b"meridian_borrower_state",
+            lending_pool.key().as_ref(),
+            borrower.key().as_ref(),
+            &borrower_profile.loan_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub borrower_state: Box<Account<'info, LoanState>>,
+    #[account(
+        init,
+        payer = borrower,
+        space = 8 + VerificationRequest::INIT_SPACE,
+        seeds = [b"meridian_verification_request", borrower_state.key().as_ref()],
+        bump
+    )]
+    pub verification_request: Box<Account<'info, VerificationRequest>>,
+    ///CHECK: Safe,Will be created on the client side
+    #[account(mut)]
+    pub rwa_asset: UncheckedAccount<'info>,
+    ///CHECK:
+    #[account(
+        mut,
+        seeds = [b"meridian_verification_vault", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_verification_vault
+    )]
+    pub protocol_verification_vault: UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+    ///CHECK: SAFE
+    pub mpl_core_program: AccountInfo<'info>,
+}
+
+impl<'info> DepositForVerification<'info> {
+    pub fn deposit_for_verification(&mut self, bumps: &DepositForVerificationBumps) -> Result<()> {
+        require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+
+        TransferV1CpiBuilder::new(&self.mpl_core_program.to_account_info())
+            .payer(&self.borrower.to_account_info())
+            .asset(&self.rwa_asset.to_account_info())
+            .authority(Some(&self.borrower.to_account_info()))
+            .new_owner(&self.protocol_verification_vault)
+            .invoke()?;
+
+        let profile = &mut self.borrower_profile;
+        profile.lending_pool = self.lending_pool.key();
+        profile.borrower = self.borrower.key();
+        profile.bump = bumps.borrower_profile;
+
+        let loan = &mut self.borrower_state;
+        loan.lending_pool = self.lending_pool.key();
+        loan.borrower = self.borrower.key();
+        loan.loan_id = profile.loan_count;
+        loan.nft_mint = self.rwa_asset.key();
+        loan.verification_id += 1;
+        loan.bump_borrower_state = bumps.borrower_state;
+
+        let request = &mut self.verification_request;
+        request.lending_pool = self.lending_pool.key();
+        request.loan = loan.key();
+        request.borrower = self.borrower.key();
+        request.rwa_asset = self.rwa_asset.key();
+        request.verification_id = loan.verification_id;
+        request.status = VerificationStatus::Pending;
+        request.requested_at = Clock::get()?.unix_timestamp;
+        request.review_deadline = request.requested_at + self.lending_pool.verification_sla;
+        request.bump = bumps.verification_request;
+
+        profile.loan_count += 1;
+        profile.open_loans += 1;
+
+        msg!(
+            "Asset transferred for verification: {}",
+            &self.rwa_asset.key()
+        );
+        msg!(
+            "Your loan id is: {}, verification id is: {}",
+            loan.loan_id,
+            loan.verification_id
+        );
+        Ok(())
+    }
+}
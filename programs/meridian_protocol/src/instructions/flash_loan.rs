@@ -0,0 +1,166 @@
+use crate::errors::Errors;
+use crate::math::{mul_div_ceil, to_u64, BPS};
+use crate::states::LendingPool;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::{
+    get_instruction_relative, load_current_index_checked, load_instruction_at_checked,
+};
+use anchor_lang::Discriminator;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked};
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Index of the lending pool in the FlashLoan accounts, checked on the flash_repay instruction
+const FLASH_REPAY_POOL_ACCOUNT_INDEX: usize = 2;
+
+//e Shared by flash_borrow and flash_repay, both have to be in the same transaction
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(mut)]
+    pub borrower: Signer<'info>,
+    pub mint_usdc: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_usdc,
+        associated_token::authority = lending_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint_usdc,
+        token::token_program = token_program,
+    )]
+    pub borrower_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    ///CHECK: Instructions sysvar, used to find the matching flash_repay
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FlashLoan<'info> {
+    //e Lends idle usdc for the rest of the transaction, a flash_repay for this pool has to come later in it
+    pub fn flash_borrow(&mut self, amount: u64) -> Result<()> {
+        require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+        require!(amount > 0, Errors::NullBorrowNotAllowed);
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        require!(
+            amount <= self.lending_pool.available_liquidity()?.min(self.lending_pool_usdc_ata.amount),
+            Errors::InsufficientLiquidityToBorrow
+        );
+        self.validate_flash_repay()?;
+
+        let lending_pool_owner = self.lending_pool.owner.key();
+        let usdc_mint = self.lending_pool.usdc_mint.key();
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
+        let seeds: &[&[&[u8]]] = &[&[
+            b"meridian_pool",
+            lending_pool_owner.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
+            &[self.lending_pool.bump_lending_pool],
+        ]];
+
+        let accounts = TransferChecked {
+            from: self.lending_pool_usdc_ata.to_account_info(),
+            to: self.borrower_usdc_ata.to_account_info(),
+            mint: self.mint_usdc.to_account_info(),
+            authority: self.lending_pool.to_account_info(),
+        };
+        let cpi_ctx =
+            CpiContext::new_with_signer(self.token_program.to_account_info(), accounts, seeds);
+        transfer_checked(cpi_ctx, amount, self.mint_usdc.decimals)?;
+
+        //e total_idle_usdc isn't touched, the usdc is back before the transaction ends and
+        //e the LP exchange rate shouldn't dip while it's out
+        self.lending_pool.flash_loan_active = true;
+        self.lending_pool.flash_loan_amount = amount;
+
+        msg!("Flash borrowed: {}", amount);
+        Ok(())
+    }
+
+    //e Pays back the flash loan plus the flash fee, the fee stays in the pool for LPs
+    pub fn flash_repay(&mut self) -> Result<()> {
+        require!(
+            self.lending_pool.flash_loan_active,
+            Errors::FlashLoanNotActive
+        );
+
+        let amount = self.lending_pool.flash_loan_amount;
+        let flash_fee = to_u64(mul_div_ceil(
+            amount as u128,
+            self.lending_pool.flash_fee_bps as u128,
+            BPS,
+        )?)?;
+
+        let accounts = TransferChecked {
+            from: self.borrower_usdc_ata.to_account_info(),
+            to: self.lending_pool_usdc_ata.to_account_info(),
+            mint: self.mint_usdc.to_account_info(),
+            authority: self.borrower.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
+        transfer_checked(cpi_ctx, amount + flash_fee, self.mint_usdc.decimals)?;
+
+        self.lending_pool.total_idle_usdc += flash_fee;
+        self.lending_pool.flash_loan_active = false;
+        self.lending_pool.flash_loan_amount = 0;
+
+        msg!("Flash loan repaid: {} (fee: {})", amount, flash_fee);
+        Ok(())
+    }
+
+    //e flash_borrow has to be called directly by the transaction, not through a CPI, and be followed by
+    //e a flash_repay on the same pool. If the repay fails the whole transaction, borrow included, reverts
+    fn validate_flash_repay(&self) -> Result<()> {
+        let instructions = self.instructions.to_account_info();
+
+        let current_instruction = get_instruction_relative(0, &instructions)?;
+        require!(
+            current_instruction.program_id == crate::ID,
+            Errors::FlashLoanCpiNotAllowed
+        );
+
+        let mut index = load_current_index_checked(&instructions)? as usize + 1;
+        while let Ok(instruction) = load_instruction_at_checked(index, &instructions) {
+            if instruction.program_id == crate::ID
+                && instruction
+                    .data
+                    .starts_with(crate::instruction::FlashRepay::DISCRIMINATOR)
+            {
+                let repay_pool = instruction
+                    .accounts
+                    .get(FLASH_REPAY_POOL_ACCOUNT_INDEX)
+                    .map(|account| account.pubkey);
+                if repay_pool == Some(self.lending_pool.key()) {
+                    return Ok(());
+                }
+            }
+            index += 1;
+        }
+
+        err!(Errors::FlashRepayMissing)
+    }
+}
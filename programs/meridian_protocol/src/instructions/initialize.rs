@@ -1,4 +1,10 @@
-use crate::states::{AdminRegistry, LendingPool, MockOracleState};
+use crate::constants::{
+    DEFAULT_ATTESTATION_TOLERANCE_BPS, DEFAULT_FLASH_FEE_BPS, DEFAULT_GRACE_PERIOD, DEFAULT_LATE_FEE_BPS, DEFAULT_MAX_MISSED_INSTALLMENTS,
+    DEFAULT_MAX_UTILIZATION_BPS, DEFAULT_PENALTY_APR_BPS,
+    DEFAULT_RESERVE_FACTOR_BPS, DEFAULT_RESERVE_RATIO_BPS, INTEREST_MODE_SIMPLE,
+    DEFAULT_VERIFICATION_QUORUM, DEFAULT_VERIFICATION_SLA, ORIGINATION_FEE_MODE_CAPITALIZE, RATE_MODEL_STEP,
+};
+use crate::states::{AdminRegistry, LendingPool, MockOracleState, RateModel, RatePoint};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::Token;
@@ -11,24 +17,28 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 // ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
 
 #[derive(Accounts)]
+#[instruction(pool_id: u64)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     #[account(mut)]
     pub mint: Box<InterfaceAccount<'info, Mint>>,
-    #[account(mut)]
-    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
     #[account(
-        init_if_needed,
+        init,
         space = 8  + LendingPool::INIT_SPACE,
         payer = authority,
-        seeds = [b"meridian_pool",authority.key().as_ref()],
+        seeds = [
+            b"meridian_pool",
+            authority.key().as_ref(),
+            mint.key().as_ref(),
+            &pool_id.to_le_bytes()
+        ],
         bump
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
     #[account(
         init_if_needed,
-        space = 8  + AdminRegistry::space(5),
+        space = 8  + AdminRegistry::space(AdminRegistry::MAX_ADMINS),
         payer = authority,
         seeds = [b"meridian_pool_admin_registry",lending_pool.key().as_ref()],
         bump
@@ -42,30 +52,50 @@ pub struct Initialize<'info> {
         bump
     )]
     pub mock_oracle: Box<Account<'info, MockOracleState>>,
+    #[account(
+        init,
+        space = 8 + RateModel::INIT_SPACE,
+        payer = authority,
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"meridian_lp_mint", lending_pool.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = lending_pool,
+        mint::token_program = token_program,
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         init_if_needed,
         payer = authority,
         associated_token::mint = mint,
-        associated_token::authority = authority,
+        associated_token::authority = lending_pool,
         associated_token::token_program = token_program,
     )]
     pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-         init_if_needed,
+        init_if_needed,
         payer = authority,
         associated_token::mint = mint_lp,
-        associated_token::authority = authority,
+        associated_token::authority = lending_pool,
         associated_token::token_program = token_program,
     )]
     pub lending_pool_lp_ata: Box<InterfaceAccount<'info, TokenAccount>>,
-    // #[account(
-    //     init_if_needed,
-    //     payer = authority,
-    //     associated_token::mint = mint,
-    //     associated_token::authority = lending_pool,
-    //     associated_token::token_program = token_program,
-    // )]
-    // pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"meridian_fee_vault", lending_pool.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = lending_pool,
+        token::token_program = token_program,
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     ///CHECK: Protocol PDA where the liquidation seized collateral rwa will be sent
     #[account(
         mut,
@@ -88,6 +118,7 @@ pub struct Initialize<'info> {
 impl<'info> Initialize<'info> {
     pub fn initialize_pool(
         &mut self,
+        pool_id: u64,
         loan_to_value_bps: u16,
         utilization_rate_tier_1_bps: u16,
         utilization_rate_tier_2_bps: u16,
@@ -112,17 +143,24 @@ impl<'info> Initialize<'info> {
         let mock_oracle = &mut self.mock_oracle;
 
         lending_pool.owner = self.authority.key();
+        lending_pool.pool_id = pool_id;
         lending_pool.admin_registry = self.admin_registry.key();
         lending_pool.withdrawal_epoch = withdrawal_epoch;
         lending_pool.protocol_admin_count = 0;
         lending_pool.usdc_mint = self.mint.key();
         lending_pool.protocol_usdc_vault = self.lending_pool_usdc_ata.key();
+        lending_pool.protocol_lp_vault = self.lending_pool_lp_ata.key();
+        lending_pool.lp_mint = self.mint_lp.key();
+        lending_pool.rate_model = self.rate_model.key();
 
         //BUMPS
         lending_pool.bump_lending_pool = bumps.lending_pool;
         lending_pool.bump_seize_vault = bumps.protocol_seize_vault;
         lending_pool.bump_verification_vault = bumps.protocol_verification_vault;
         lending_pool.bump_admin_registry = bumps.admin_registry;
+        lending_pool.bump_lp_mint = bumps.mint_lp;
+        lending_pool.bump_rate_model = bumps.rate_model;
+        lending_pool.bump_fee_vault = bumps.protocol_fee_vault;
 
         //MOCK ORACLE
         mock_oracle.admin = self.authority.key();
@@ -135,8 +173,25 @@ impl<'info> Initialize<'info> {
 
         lending_pool.early_withdrawal_fee_bps = early_withdrawal_fee_bps;
         lending_pool.origination_fee_bps = origination_fee_bps;
+        lending_pool.refinance_fee_bps = 0;
+        lending_pool.reserve_factor_bps = DEFAULT_RESERVE_FACTOR_BPS;
+        lending_pool.origination_fee_mode = ORIGINATION_FEE_MODE_CAPITALIZE;
+        lending_pool.flash_fee_bps = DEFAULT_FLASH_FEE_BPS;
+        lending_pool.verification_sla = DEFAULT_VERIFICATION_SLA;
+        lending_pool.verification_quorum = DEFAULT_VERIFICATION_QUORUM;
+        lending_pool.attestation_tolerance_bps = DEFAULT_ATTESTATION_TOLERANCE_BPS;
         lending_pool.loan_to_value_bps = loan_to_value_bps;
 
+        //BORROW LIMITS, OWNER CAN TUNE THEM LATER
+        lending_pool.reserve_ratio_bps = DEFAULT_RESERVE_RATIO_BPS;
+        lending_pool.max_utilization_bps = DEFAULT_MAX_UTILIZATION_BPS;
+
+        //TERM LOANS, OWNER CAN TUNE THEM LATER
+        lending_pool.grace_period = DEFAULT_GRACE_PERIOD;
+        lending_pool.penalty_apr_bps = DEFAULT_PENALTY_APR_BPS;
+        lending_pool.late_fee_bps = DEFAULT_LATE_FEE_BPS;
+        lending_pool.max_missed_installments = DEFAULT_MAX_MISSED_INSTALLMENTS;
+
         //UTILIZATION RATE TIERS...
         lending_pool.utilization_rate_tier_1_bps = utilization_rate_tier_1_bps;
         lending_pool.utilization_rate_tier_2_bps = utilization_rate_tier_2_bps;
@@ -151,9 +206,38 @@ impl<'info> Initialize<'info> {
         lending_pool.apr_tier_4_bps = apr_tier_4_bps;
         lending_pool.apr_tier_5_bps = apr_tier_5_bps;
 
+        //RATE MODEL, STARTS AS THE FIVE STEP TIERS. OWNER CAN SWITCH IT TO A CURVE LATER
+        let rate_model = &mut self.rate_model;
+        rate_model.lending_pool = lending_pool.key();
+        rate_model.model_type = RATE_MODEL_STEP;
+        rate_model.bump = bumps.rate_model;
+        let tiers = [
+            (utilization_rate_tier_1_bps, apr_tier_1_bps),
+            (utilization_rate_tier_2_bps, apr_tier_2_bps),
+            (utilization_rate_tier_3_bps, apr_tier_3_bps),
+            (utilization_rate_tier_4_bps, apr_tier_4_bps),
+            (utilization_rate_tier_5_bps, apr_tier_5_bps),
+        ]
+        .map(|(utilization_bps, apr_bps)| RatePoint {
+            utilization_bps,
+            apr_bps,
+        });
+        RateModel::validate_points(&tiers)?;
+        rate_model.points[..tiers.len()].copy_from_slice(&tiers);
+        rate_model.point_count = tiers.len() as u8;
+
         lending_pool.is_locked = false;
+        lending_pool.flash_loan_active = false;
+
+        //e Borrow index starts at 0 and moves forward from pool creation
+        lending_pool.borrow_index = 0;
+        lending_pool.last_accrual_ts = Clock::get()?.unix_timestamp;
+        lending_pool.interest_mode = INTEREST_MODE_SIMPLE;
+        lending_pool.compounding_period = 0;
+        lending_pool.variable_rate_index = 0;
+        lending_pool.fixed_rate_premium_bps = 0;
 
-        // lending_pool.protocol_fee_vault = self.protocol_fee_vault.key();
+        lending_pool.protocol_fee_vault = self.protocol_fee_vault.key();
 
         self.log_state();
 
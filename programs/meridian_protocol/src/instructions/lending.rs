@@ -1,6 +1,7 @@
 use crate::errors::Errors;
-use crate::states::{Lender, LendingPool};
+use crate::states::{Lender, LendingPool, RateModel};
 use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::Token;
 use anchor_spl::token_interface::{
@@ -15,39 +16,51 @@ use anchor_spl::token_interface::{
 
 #[derive(Accounts)]
 pub struct Lending<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
     #[account(mut)]
     pub lender: Signer<'info>,
     #[account(mut)]
     pub mint: Box<InterfaceAccount<'info, Mint>>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"meridian_lp_mint", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_lp_mint
+    )]
     pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",lending_pool.owner.as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = lending_pool.owner,
+        associated_token::authority = lending_pool,
         associated_token::token_program = token_program,
     )]
     pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         init_if_needed,
         payer = lender,
-        space = 8 + Lender::INIT_SPACE,
-        seeds = [b"lender_seed", lender.key().as_ref()],
+        space = Lender::space(0),
+        seeds = [b"lender_seed", lending_pool.key().as_ref(), lender.key().as_ref()],
         bump
     )]
     pub lender_state: Box<Account<'info, Lender>>,
     #[account(
         mut,
         associated_token::mint = mint_lp,
-        associated_token::authority = lending_pool.owner,
+        associated_token::authority = lending_pool,
         associated_token::token_program = token_program,
     )]
     pub lending_pool_lp_ata: Box<InterfaceAccount<'info, TokenAccount>>,
@@ -73,9 +86,18 @@ pub struct Lending<'info> {
 }
 
 impl<'info> Lending<'info> {
-    pub fn deposit_liquidity(&mut self, amount_to_deposit: u64) -> Result<()> {
-        require!(amount_to_deposit.clone() > 0, Errors::NullDepositNotAllowed);
+    pub fn deposit_liquidity(&mut self, amount_to_deposit: u64, bumps: &LendingBumps) -> Result<()> {
+        require!(amount_to_deposit > 0, Errors::NullDepositNotAllowed);
         require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+
+        //e Shares are priced against the pool assets before this deposit lands, interest included
+        self.lending_pool
+            .accrue_interest(&self.rate_model, Clock::get()?.unix_timestamp)?;
+        let amount_shares_to_mint = self.calculate_shares_to_mint(amount_to_deposit)?;
 
         let accounts = TransferChecked {
             from: self.lender_usdc_ata.to_account_info(),
@@ -89,63 +111,88 @@ impl<'info> Lending<'info> {
 
         transfer_checked(cpi_context, amount_to_deposit, self.mint.decimals)?;
 
-        self.lender_state.deposited_at = Clock::get()?.unix_timestamp;
-        self.lender_state.total_deposited = amount_to_deposit;
+        self.lender_state.lending_pool = self.lending_pool.key();
         self.lender_state.owner = self.lender.key();
+        self.lender_state.bump = bumps.lender_state;
+
+        self.lending_pool.total_idle_usdc += amount_to_deposit;
+        self.lending_pool.total_deposited_usdc += amount_to_deposit;
 
         msg!("Amount Deposited: {}", amount_to_deposit);
 
-        self.mint_shares(amount_to_deposit)?;
+        self.mint_shares(amount_shares_to_mint)?;
+
+        //e Every deposit is its own lot, so it runs its own withdrawal epoch
+        self.grow_lender_state()?;
+        self.lender_state.add_lot(
+            amount_to_deposit,
+            amount_shares_to_mint,
+            Clock::get()?.unix_timestamp,
+        )?;
 
         Ok(())
     }
 
-    fn mint_shares(&mut self, amount_deposited: u64) -> Result<()> {
-        let amount_shares_to_mint = self.calculate_shares_to_mint(amount_deposited);
-
-        let authority = self.lending_pool.owner;
+    //e Makes room for one more lot, the lender pays the extra rent
+    fn grow_lender_state(&mut self) -> Result<()> {
+        let lender_state_info = self.lender_state.to_account_info();
+        let required_space = Lender::space(self.lender_state.lots.len() + 1);
+
+        if required_space <= lender_state_info.data_len() {
+            return Ok(());
+        }
+
+        let lamports_needed = Rent::get()?
+            .minimum_balance(required_space)
+            .saturating_sub(lender_state_info.lamports());
+
+        if lamports_needed > 0 {
+            let accounts = Transfer {
+                from: self.lender.to_account_info(),
+                to: lender_state_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), accounts);
+            transfer(cpi_ctx, lamports_needed)?;
+        }
+
+        lender_state_info.resize(required_space)?;
+        Ok(())
+    }
 
+    fn mint_shares(&mut self, amount_shares_to_mint: u64) -> Result<()> {
         let accounts = MintTo {
             mint: self.mint_lp.to_account_info(),
-            authority: self.authority.to_account_info(),
+            authority: self.lending_pool.to_account_info(),
             to: self.lender_lp_ata.to_account_info(),
         };
 
         let cpi_program = self.token_program.to_account_info();
 
-        // let lending_pool_owner = self.lending_pool.owner.key();
-        // let seeds = &[
-        //     b"meridian_pool",
-        //     lending_pool_owner.as_ref(),
-        //     &[self.lending_pool.bump_lending_pool],
-        // ];
+        //e LP mint authority is the pool PDA, so the pool signs the mint
+        let lending_pool_owner = self.lending_pool.owner.key();
+        let usdc_mint = self.lending_pool.usdc_mint.key();
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
+        let seeds = &[
+            &b"meridian_pool"[..],
+            lending_pool_owner.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
+            &[self.lending_pool.bump_lending_pool],
+        ];
 
-        // let signer_seeds = &[&seeds[..]];
+        let signer_seeds = &[&seeds[..]];
 
-        let cpi_ctx = CpiContext::new(cpi_program, accounts);
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, accounts, signer_seeds);
 
         mint_to(cpi_ctx, amount_shares_to_mint)?;
         msg!("Minted Lp Tokens to: {}", self.lender_lp_ata.key());
 
-        self.lender_state.lp_shares += amount_shares_to_mint;
         self.lending_pool.lp_total_supply += amount_shares_to_mint;
 
         Ok(())
     }
 
-    pub fn calculate_shares_to_mint(&mut self, deposit_amount: u64) -> u64 {
-        let lp_supply = self.lending_pool.lp_total_supply;
-        let total_liquidity_in_pool = self.lending_pool.total_deposited_usdc;
-
-        if lp_supply == 0 {
-            return deposit_amount;
-        };
-
-        let shares_to_mint = deposit_amount
-            .checked_mul(lp_supply)
-            .unwrap()
-            .checked_div(total_liquidity_in_pool)
-            .unwrap();
-        return shares_to_mint;
+    pub fn calculate_shares_to_mint(&mut self, deposit_amount: u64) -> Result<u64> {
+        self.lending_pool.shares_for_amount(deposit_amount)
     }
 }
@@ -1,5 +1,5 @@
 use crate::errors::Errors;
-use crate::states::{LendingPool, LoanState, MockOracleState};
+use crate::states::{BorrowerProfile, LendingPool, LoanState, MockOracleState, RateModel};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::Token;
@@ -17,22 +17,30 @@ use mpl_core::instructions::TransferV1CpiBuilder;
 
 #[derive(Accounts)]
 pub struct Liquidate<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
     #[account(mut)]
     pub liquidator: Signer<'info>,
     #[account(mut)]
     pub mint_usdc: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",lending_pool.owner.as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
     #[account(
         mut,
         associated_token::mint = mint_usdc,
-        associated_token::authority = lending_pool.owner,
+        associated_token::authority = lending_pool,
         associated_token::token_program = token_program,
     )]
     pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
@@ -46,12 +54,27 @@ pub struct Liquidate<'info> {
     pub liquidator_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut,
-        seeds = [b"meridian_borrower_state", borrower_state.borrower.key().as_ref()],
+        seeds = [
+            b"meridian_borrower_profile",
+            lending_pool.key().as_ref(),
+            borrower_state.borrower.key().as_ref()
+        ],
+        bump = borrower_profile.bump
+    )]
+    pub borrower_profile: Box<Account<'info, BorrowerProfile>>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_borrower_state",
+            lending_pool.key().as_ref(),
+            borrower_state.borrower.key().as_ref(),
+            &borrower_state.loan_id.to_le_bytes()
+        ],
         bump = borrower_state.bump_borrower_state
     )]
     pub borrower_state: Box<Account<'info, LoanState>>,
-    ///CHECK: Safe,Will be created on the client side
-    #[account(mut)]
+    ///CHECK: The asset pledged to this loan
+    #[account(mut, address = borrower_state.nft_mint)]
     pub rwa_asset: UncheckedAccount<'info>,
     ///CHECK: Protocol PDA where the liquidation seized collateral rwa will be sent
     #[account(
@@ -76,19 +99,38 @@ pub struct Liquidate<'info> {
 impl<'info> Liquidate<'info> {
     pub fn liquidate(&mut self) -> Result<()> {
         require!(
-            self.borrower_state.loan_status == 0,
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        //e Active loans (0) are liquidatable when unhealthy, defaulted loans (2) whatever their health
+        let is_defaulted = self.borrower_state.loan_status == 2;
+        require!(
+            self.borrower_state.loan_status == 0 || is_defaulted,
             Errors::CannotLiquidate
         );
         let liquidation_penalty = self.calculate_liquidation_penalty()?;
         let (_total_debt_to_repay, health_factor) =
             self.total_debt_to_repay(liquidation_penalty)?; //e Didn't calculate differently to avoid circular dependencies
 
-        require!(health_factor < 1, Errors::CannotLiquidate);
+        require!(is_defaulted || health_factor < 1, Errors::CannotLiquidate);
 
         self.transfer_asset_to_seize_vault()?;
         self.transfer_penalty_shares_to_the_liquidator(liquidation_penalty)?;
 
-        self.borrower_state.loan_status = 2;
+        //e The loan won't be repaid in USDC anymore, principal and the LP share of booked interest are written down against the LPs
+        self.lending_pool.remove_loan_weight(&self.borrower_state);
+        //e The booked reserve cut is written off, it won't be paid either
+        let interest_accrued = self.borrower_state.interest_accrued;
+        let reserve = self.borrower_state.reserve_accrued.min(interest_accrued);
+        let lp_interest = interest_accrued - reserve;
+        self.lending_pool.total_bad_debt += self.borrower_state.principal_borrowed + lp_interest;
+        self.lending_pool.release_interest(reserve);
+        self.lending_pool.release_reserves(reserve);
+        self.borrower_state.reserve_accrued = 0;
+
+        self.borrower_state.loan_status = 3; //liquidated
+        self.borrower_state.current_owner_asset = self.protocol_seize_vault.key();
+        self.borrower_profile.open_loans -= 1;
 
         Ok(())
     }
@@ -107,13 +149,17 @@ impl<'info> Liquidate<'info> {
         let accounts = TransferChecked {
             from: self.lending_pool_usdc_ata.to_account_info(),
             to: self.liquidator_usdc_ata.to_account_info(),
-            authority: self.authority.to_account_info(),
+            authority: self.lending_pool.to_account_info(),
             mint: self.mint_usdc.to_account_info(),
         };
-        let lending_pool_owner = self.authority.key();
+        let lending_pool_owner = self.lending_pool.owner.key();
+        let usdc_mint = self.lending_pool.usdc_mint.key();
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
         let seeds: &[&[&[u8]]] = &[&[
             b"meridian_pool",
             lending_pool_owner.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
             &[self.lending_pool.bump_lending_pool],
         ]];
 
@@ -121,16 +167,24 @@ impl<'info> Liquidate<'info> {
             CpiContext::new_with_signer(self.token_program.to_account_info(), accounts, seeds);
         transfer_checked(cpi_ctx, shares_to_transfer, self.mint_usdc.decimals)?;
 
-        //e overflow happening
-        // self.lending_pool.total_deposited_usdc -= shares_to_transfer;
+        //e Whatever's left of the penalty after the liquidator reward is the protocol's share. No USDC backs it
+        //e until the seized asset is sold, so it's only booked here and LP cash stays in the pool
+        let protocol_share = total_penalty - shares_to_transfer;
+        self.lending_pool.liquidation_fees_accrued += protocol_share;
+
+        self.lending_pool.total_idle_usdc -= shares_to_transfer;
         Ok(())
     }
 
     fn transfer_asset_to_seize_vault(&mut self) -> Result<()> {
         let lending_pool_owner = self.lending_pool.owner.key();
+        let usdc_mint = self.lending_pool.usdc_mint.key();
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
         let seeds: &[&[&[u8]]] = &[&[
             b"meridian_pool",
             lending_pool_owner.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
             &[self.lending_pool.bump_lending_pool],
         ]];
 
@@ -146,11 +200,11 @@ impl<'info> Liquidate<'info> {
 
     pub fn total_debt_to_repay(&mut self, liquidation_penalty: u64) -> Result<(u64, u64)> {
         //e collateral + interest accrued + liquidation_penalty_if applied
-        let total_interest_accrued_by_user = self.calculate_interest_accrued()?;
-        let principal_borrowed = self.borrower_state.principal_borrowed;
-        let origination_fee = self.borrower_state.origination_fee;
+        let current_time = Clock::get()?.unix_timestamp;
+        self.lending_pool
+            .settle_loan(&self.rate_model, &mut self.borrower_state, current_time)?;
 
-        let mut base_debt = total_interest_accrued_by_user + principal_borrowed + origination_fee;
+        let mut base_debt = self.borrower_state.base_debt();
 
         //e for testing purposes
         let test_debt = self.borrower_state.total_debt_to_repay > base_debt;
@@ -169,35 +223,9 @@ impl<'info> Liquidate<'info> {
             total_debt_to_repay = base_debt;
         }
 
-        self.borrower_state.interest_accrued += total_interest_accrued_by_user;
-
         Ok((total_debt_to_repay, health_factor))
     }
 
-    pub fn calculate_interest_accrued(&mut self) -> Result<u64> {
-        pub const SECONDS_PER_YEAR: u64 = 31_536_000;
-        let current_time = Clock::get()?.unix_timestamp;
-        let last_interest_accrued_at = self.borrower_state.last_interest_accrued;
-
-        let borrow_apr_at_the_time_of_borrow = self.borrower_state.borrow_apr_bps;
-        let time_delta = current_time - last_interest_accrued_at; //e Won't go below 0 so we can convert it to u64
-
-        let interest_accrued_numerator = self
-            .borrower_state
-            .principal_borrowed
-            .checked_mul(borrow_apr_at_the_time_of_borrow as u64)
-            .unwrap()
-            .checked_mul(time_delta as u64)
-            .unwrap();
-        let interest_accrued_denominator = 10000 * SECONDS_PER_YEAR; //e Won't overflow
-
-        let total_interest_accrued = interest_accrued_numerator
-            .checked_div(interest_accrued_denominator)
-            .unwrap();
-
-        Ok(total_interest_accrued)
-    }
-
     fn calculate_liquidation_penalty(&mut self) -> Result<u64> {
         // let total_user_debt = self.total_debt_to_repay()?;
         let total_user_debt = self.borrower_state.principal_borrowed;
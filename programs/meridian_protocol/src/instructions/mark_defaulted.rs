@@ -0,0 +1,86 @@
+use crate::constants::REPAYMENT_TYPE_INSTALLMENT;
+use crate::errors::Errors;
+use crate::states::{LendingPool, LoanState, RateModel};
+use anchor_lang::prelude::*;
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Permissionless, anyone can flag a loan left unpaid past maturity + grace period,
+//e or with too many missed installments, so it can be liquidated
+#[derive(Accounts)]
+pub struct MarkDefaulted<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_borrower_state",
+            lending_pool.key().as_ref(),
+            borrower_state.borrower.key().as_ref(),
+            &borrower_state.loan_id.to_le_bytes()
+        ],
+        bump = borrower_state.bump_borrower_state
+    )]
+    pub borrower_state: Box<Account<'info, LoanState>>,
+}
+
+impl<'info> MarkDefaulted<'info> {
+    pub fn mark_defaulted(&mut self) -> Result<()> {
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        let current_time = Clock::get()?.unix_timestamp;
+
+        require!(
+            self.borrower_state.loan_status == 0
+                && self.borrower_state.principal_borrowed > 0
+                && self.borrower_state.maturity_ts > 0,
+            Errors::CannotMarkDefaulted
+        );
+
+        //e Bring the debt up to date, penalty interest and late fees included, before freezing the status
+        self.lending_pool
+            .settle_loan(&self.rate_model, &mut self.borrower_state, current_time)?;
+        let late_fee_bps = self.lending_pool.late_fee_bps;
+        self.borrower_state
+            .update_missed_installments(late_fee_bps, current_time)?;
+
+        let past_grace_period =
+            current_time > self.borrower_state.maturity_ts + self.lending_pool.grace_period;
+        let too_many_missed = self.borrower_state.repayment_type == REPAYMENT_TYPE_INSTALLMENT
+            && self.borrower_state.missed_installments
+                >= self.lending_pool.max_missed_installments;
+        require!(
+            past_grace_period || too_many_missed,
+            Errors::CannotMarkDefaulted
+        );
+
+        self.borrower_state.loan_status = 2; //liquidatable
+        msg!(
+            "Loan {} of {} defaulted, debt: {}",
+            self.borrower_state.loan_id,
+            self.borrower_state.borrower,
+            self.borrower_state.base_debt()
+        );
+        Ok(())
+    }
+}
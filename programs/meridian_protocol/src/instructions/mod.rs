@@ -38,3 +38,30 @@ pub use borrow::*;
 
 pub mod mock_oracle;
 pub use mock_oracle::*;
+
+pub mod pool_info;
+pub use pool_info::*;
+
+pub mod withdrawal_queue;
+pub use withdrawal_queue::*;
+
+pub mod deposit_for_verification;
+pub use deposit_for_verification::*;
+
+pub mod configure_pool;
+pub use configure_pool::*;
+
+pub mod accrue_interest;
+pub use accrue_interest::*;
+
+pub mod mark_defaulted;
+pub use mark_defaulted::*;
+
+pub mod withdraw_protocol_fees;
+pub use withdraw_protocol_fees::*;
+
+pub mod settle_liquidation_fees;
+pub use settle_liquidation_fees::*;
+
+pub mod flash_loan;
+pub use flash_loan::*;
@@ -0,0 +1,68 @@
+use crate::states::{LendingPool, ProtocolFees, RateModel, RateQuote};
+use anchor_lang::prelude::*;
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Read only view over the pool, used by the getters
+#[derive(Accounts)]
+pub struct PoolInfo<'info> {
+    #[account(
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
+}
+
+impl<'info> PoolInfo<'info> {
+    pub fn get_exchange_rate(&self) -> Result<u128> {
+        let pool = &self.lending_pool;
+        msg!(
+            "Idle: {}, Borrowed: {}, Interest Accrued: {}, Bad Debt: {}, LP Supply: {}",
+            pool.total_idle_usdc,
+            pool.total_borrowed,
+            pool.total_interest_accrued,
+            pool.total_bad_debt,
+            pool.lp_total_supply
+        );
+        pool.exchange_rate()
+    }
+
+    //e Borrow APR a new variable loan would pay right now, and what that works out to for lenders
+    pub fn get_rates(&self) -> Result<RateQuote> {
+        let pool = &self.lending_pool;
+        Ok(RateQuote {
+            utilization_bps: pool.utilization_rate_bps()?,
+            borrow_apr_bps: pool.current_borrow_rate_bps(&self.rate_model)?,
+            supply_apy_bps: pool.supply_apy_bps(&self.rate_model)?,
+        })
+    }
+
+    //e Fees and reserves the protocol collected, and what's left to sweep from the fee vault
+    pub fn get_protocol_fees(&self) -> Result<ProtocolFees> {
+        let pool = &self.lending_pool;
+        Ok(ProtocolFees {
+            origination_fees: pool.origination_fees_collected,
+            early_withdrawal_fees: pool.early_withdrawal_fees_collected,
+            liquidation_fees_accrued: pool.liquidation_fees_accrued,
+            liquidation_fees: pool.liquidation_fees_collected,
+            reserves_accrued: pool.total_reserves_accrued,
+            reserves: pool.reserve_fees_collected,
+            withdrawn: pool.protocol_fees_withdrawn,
+            available: pool.protocol_fees_available(),
+        })
+    }
+}
@@ -15,7 +15,12 @@ pub struct RemoveAdmin<'info> {
     pub authority: Signer<'info>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",authority.key().as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
@@ -41,8 +46,13 @@ impl<'info> RemoveAdmin<'info> {
 
         let admin_index = self.admin_registry.admins.iter().position(|&a| a == admin);
         require!(admin_index.is_some(), Errors::AdminInvalid);
+        //e Can't drop below the verification quorum, pending verifications would never be decided
+        require!(
+            self.admin_registry.admins.len() > self.lending_pool.verification_quorum as usize,
+            Errors::QuorumUnreachable
+        );
 
-        self.remove_admin(admin)?;
+        self.admin_registry.remove_admin(admin_index.unwrap())?;
 
         msg!("Admin removed : {}", admin);
         Ok(())
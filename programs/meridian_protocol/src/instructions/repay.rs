@@ -4,8 +4,9 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::Token;
 
 // use crate::constants::{GOLD_USD_PRICE_FEED, MAX_AGE};
+use crate::constants::REPAYMENT_TYPE_INSTALLMENT;
 use crate::errors::Errors;
-use crate::states::{LendingPool, LoanState, MockOracleState};
+use crate::states::{BorrowerProfile, LendingPool, LoanState, MockOracleState, RateModel};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{
@@ -28,20 +29,48 @@ pub struct Repay<'info> {
     pub mint_usdc: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",lending_pool.owner.as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
     #[account(
         mut,
         associated_token::mint = mint_usdc,
-        associated_token::authority = lending_pool.owner,
+        associated_token::authority = lending_pool,
         associated_token::token_program = token_program,
     )]
     pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
         mut,
-        seeds = [b"meridian_borrower_state", borrower.key().as_ref()],
+        seeds = [b"meridian_fee_vault", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_fee_vault
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"meridian_borrower_profile", lending_pool.key().as_ref(), borrower.key().as_ref()],
+        bump = borrower_profile.bump
+    )]
+    pub borrower_profile: Box<Account<'info, BorrowerProfile>>,
+    #[account(
+        mut,
+        has_one = borrower,
+        seeds = [
+            b"meridian_borrower_state",
+            lending_pool.key().as_ref(),
+            borrower.key().as_ref(),
+            &borrower_state.loan_id.to_le_bytes()
+        ],
         bump = borrower_state.bump_borrower_state
     )]
     pub borrower_state: Box<Account<'info, LoanState>>,
@@ -52,8 +81,8 @@ pub struct Repay<'info> {
         associated_token::token_program = token_program,
     )]
     pub borrower_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
-    ///CHECK: Safe,Will be created on the client side
-    #[account(mut)]
+    ///CHECK: The asset pledged to this loan
+    #[account(mut, address = borrower_state.nft_mint)]
     pub rwa_asset: UncheckedAccount<'info>,
     ///CHECK:
     #[account(
@@ -78,18 +107,71 @@ pub struct Repay<'info> {
 
 impl<'info> Repay<'info> {
     pub fn repay(&mut self, amount_to_repay: u64,current_time: i64) -> Result<()> {
-        //e Loan can be repaid only if it's not repaid earlier or your asset is not liquidated
         require!(
-            self.borrower_state.loan_status == 0,
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        //e Loan can be repaid only if it's not repaid earlier or your asset is not liquidated.
+        //e A defaulted loan can still be paid off until someone liquidates it
+        require!(
+            self.borrower_state.loan_status == 0 || self.borrower_state.loan_status == 2,
             Errors::CannotRepayLoan
         );
+        require!(amount_to_repay > 0, Errors::RepayAmountNotEnough);
+
+        //e Books interest up to now, so the allocation below starts from a fresh checkpoint
+        self.total_debt_to_repay(current_time)?;
 
-        // let current_time = self.get_current_time()?;
-        let total_debt_to_repay = self.total_debt_to_repay(current_time)?;
+        self.process_repayment(amount_to_repay)
+    }
+
+    //e Pays the next installment of the schedule, late fees of missed ones included
+    pub fn pay_installment(&mut self, current_time: i64) -> Result<()> {
         require!(
-            amount_to_repay >= total_debt_to_repay,
-            Errors::RepayAmountNotEnough
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
         );
+        require!(
+            self.borrower_state.loan_status == 0 || self.borrower_state.loan_status == 2,
+            Errors::CannotRepayLoan
+        );
+        require!(
+            self.borrower_state.repayment_type == REPAYMENT_TYPE_INSTALLMENT,
+            Errors::NotAnInstallmentLoan
+        );
+        require!(
+            self.borrower_state.installments_paid < self.borrower_state.installment_count,
+            Errors::NoInstallmentDue
+        );
+
+        self.total_debt_to_repay(current_time)?;
+
+        let amount_due = self.borrower_state.next_installment_due();
+        self.process_repayment(amount_due)?;
+
+        let late_fee_bps = self.lending_pool.late_fee_bps;
+        self.borrower_state
+            .update_missed_installments(late_fee_bps, current_time)?;
+
+        msg!(
+            "Installment {} of {} paid",
+            self.borrower_state.installments_paid,
+            self.borrower_state.installment_count
+        );
+        Ok(())
+    }
+
+    fn process_repayment(&mut self, amount_to_repay: u64) -> Result<()> {
+        //e Overpayments are capped at the debt, the borrower is only charged what they owe
+        self.lending_pool.remove_loan_weight(&self.borrower_state);
+        let allocation = self.borrower_state.apply_repayment(amount_to_repay)?;
+        self.lending_pool.add_loan_weight(&self.borrower_state)?;
+        self.borrower_state.credit_installments(&allocation);
+        let amount_paid = allocation.total();
+        //e Origination fees and the reserve cut of the interest go to the protocol, everything else goes back to the pool
+        let reserve_cut = allocation.interest_reserve + allocation.reserve;
+        let protocol_share = allocation.origination_fee + reserve_cut;
+        let pool_share = amount_paid - protocol_share;
 
         //User transferring amount to the lending pool protocol
         let token_program = self.token_program.to_account_info();
@@ -100,17 +182,49 @@ impl<'info> Repay<'info> {
             to: self.lending_pool_usdc_ata.to_account_info(),
         };
 
-        let cpi_ctx = CpiContext::new(token_program, accounts);
+        let cpi_ctx = CpiContext::new(token_program.clone(), accounts);
+
+        transfer_checked(cpi_ctx, pool_share, self.mint_usdc.decimals)?;
+
+        if protocol_share > 0 {
+            let accounts = TransferChecked {
+                authority: self.borrower.to_account_info(),
+                mint: self.mint_usdc.to_account_info(),
+                from: self.borrower_usdc_ata.to_account_info(),
+                to: self.protocol_fee_vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(token_program, accounts);
+            transfer_checked(cpi_ctx, protocol_share, self.mint_usdc.decimals)?;
+            self.lending_pool.origination_fees_collected += allocation.origination_fee;
+            self.lending_pool.reserve_fees_collected += reserve_cut;
+        }
 
-        transfer_checked(cpi_ctx, amount_to_repay, self.mint_usdc.decimals)?;
+        //e Principal leaves the loan book, interest and late fees on top of it grow the pool assets for LPs
+        self.lending_pool.total_idle_usdc += pool_share;
+        self.lending_pool.total_borrowed -= allocation.principal;
+        self.lending_pool.release_interest(allocation.interest);
+        self.lending_pool.release_reserves(allocation.interest_reserve);
+
+        msg!(
+            "Repaid {} (interest: {}, fees: {}, principal: {})",
+            amount_paid,
+            allocation.interest,
+            allocation.fees,
+            allocation.principal
+        );
 
-        self.borrower_state.loan_status = 1; //repaid
-        self.borrower_state.principal_borrowed = 0;
-        self.borrower_state.origination_fee = 0;
-        self.borrower_state.outstanding_debt = 0;
-        self.borrower_state.total_debt_to_repay = 0 ;
-        //Transferring asset back to user
-        self.transfer_asset_to_user()?;
+        let debt_left = self.borrower_state.base_debt();
+        self.borrower_state.total_debt_to_repay = debt_left;
+        self.borrower_state.current_health_factor = self.calculate_health_factor(debt_left)?;
+
+        //e Collateral stays with the pool until the loan is fully paid off
+        if debt_left == 0 {
+            self.borrower_state.loan_status = 1; //repaid
+            //Transferring asset back to user
+            self.transfer_asset_to_user()?;
+            self.borrower_state.current_owner_asset = self.borrower.key();
+            self.borrower_profile.open_loans -= 1;
+        }
 
         Ok(())
     }
@@ -122,9 +236,17 @@ impl<'info> Repay<'info> {
 
     fn transfer_asset_to_user(&mut self) -> Result<()> {
         let key = self.lending_pool.owner.key();
+        let usdc_mint = self.lending_pool.usdc_mint.key();
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
         let bump = self.lending_pool.bump_lending_pool;
 
-        let signer_seeds: &[&[u8]] = &[b"meridian_pool", key.as_ref(), &[bump]];
+        let signer_seeds: &[&[u8]] = &[
+            b"meridian_pool",
+            key.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
+            &[bump],
+        ];
         let seeds = &[signer_seeds];
 
        
@@ -139,71 +261,20 @@ impl<'info> Repay<'info> {
     }
 
     pub fn total_debt_to_repay(&mut self,current_time: i64) -> Result<u64> {
-        //e collateral + interest accrued + liquidation_penalty_if applied
-        let total_interest_accrued_by_user = self.calculate_interest_accrued(current_time)?;
-        let principal_borrowed = self.borrower_state.principal_borrowed;
-        let origination_fee = self.borrower_state.origination_fee;
-
-        let base_debt = total_interest_accrued_by_user + principal_borrowed + origination_fee;
-
-        let health_factor = self.calculate_health_factor(base_debt)?;
-
-        let total_debt_to_repay: u64;
-
-        if health_factor < 1 {
-            let liquidation_penalty = self.calculate_liquidation_penalty()?;
-            total_debt_to_repay = liquidation_penalty + base_debt;
-        } else {
-            total_debt_to_repay = base_debt;
-        }
-
-        self.borrower_state.interest_accrued += total_interest_accrued_by_user;
-        self.borrower_state.total_debt_to_repay += total_debt_to_repay;
+        //e principal + interest accrued + origination fees. The liquidation penalty is only charged by liquidate,
+        //e a borrower paying down an unhealthy loan shouldn't be penalised for curing it
+        self.lending_pool
+            .settle_loan(&self.rate_model, &mut self.borrower_state, current_time)?;
+        let late_fee_bps = self.lending_pool.late_fee_bps;
+        self.borrower_state
+            .update_missed_installments(late_fee_bps, current_time)?;
+        let total_debt_to_repay = self.borrower_state.base_debt();
+
+        self.borrower_state.total_debt_to_repay = total_debt_to_repay;
         msg!("Total debt to repay is: {}", total_debt_to_repay);
         Ok(total_debt_to_repay)
     }
 
-    pub fn calculate_interest_accrued(&mut self,current_time: i64) -> Result<u64> {
-        pub const SECONDS_PER_YEAR: u64 = 31_536_000;
-        // let current_time = Clock::get()?.unix_timestamp;
-        let last_interest_accrued_at = self.borrower_state.last_interest_accrued;
-
-        let borrow_apr_at_the_time_of_borrow = self.borrower_state.borrow_apr_bps;
-        let time_delta = current_time - last_interest_accrued_at; //e Won't go below 0 so we can convert it to u64
-
-        if time_delta == 0{
-            msg!("No time elapsed, time is zero");
-           return Ok(0)
-        }
-
-        let interest_accrued_numerator = self
-            .borrower_state
-            .principal_borrowed
-            .checked_mul(borrow_apr_at_the_time_of_borrow as u64)
-            .unwrap()
-            .checked_mul(time_delta as u64)
-            .unwrap();
-        let interest_accrued_denominator = 10000 * SECONDS_PER_YEAR; //e Won't overflow
-
-        let total_interest_accrued = interest_accrued_numerator
-            .checked_div(interest_accrued_denominator)
-            .unwrap();
-
-        Ok(total_interest_accrued)
-    }
-
-    pub fn calculate_liquidation_penalty(&mut self) -> Result<u64> {
-        let total_user_debt = self.borrower_state.principal_borrowed;
-
-        let liquidation_penalty = total_user_debt
-            .checked_mul(self.lending_pool.liquidation_penalty_bps as u64)
-            .unwrap()
-            .checked_div(10_000)
-            .unwrap();
-
-        Ok(liquidation_penalty)
-    }
-
     pub fn calculate_health_factor(&mut self, total_debt: u64) -> Result<u64> {
         //Below Implementation is unsafe, latter is the better one...
         // let health_factor = (self.lending_pool.liquidation_threshold_bps as u64
@@ -228,7 +299,4 @@ impl<'info> Repay<'info> {
 
         Ok(health_factor)
     }
-
-    //withdraw the protocol fees to whatever account..Add a admin controlled function for that!
-
 }
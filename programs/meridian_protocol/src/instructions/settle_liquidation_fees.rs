@@ -0,0 +1,80 @@
+use crate::errors::Errors;
+use crate::states::LendingPool;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked};
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Owner brings in the proceeds of a sold seized asset, the protocol's liquidation share goes into the fee vault
+#[derive(Accounts)]
+pub struct SettleLiquidationFees<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(address = lending_pool.usdc_mint)]
+    pub mint_usdc: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        mut,
+        seeds = [b"meridian_fee_vault", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_fee_vault
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint_usdc,
+        token::authority = authority,
+        token::token_program = token_program,
+    )]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> SettleLiquidationFees<'info> {
+    pub fn settle_liquidation_fees(&mut self, amount: u64) -> Result<()> {
+        require!(
+            self.authority.key() == self.lending_pool.owner.key(),
+            Errors::OnlyAuthority
+        );
+        require!(amount > 0, Errors::NullDepositNotAllowed);
+        require!(
+            amount <= self.lending_pool.liquidation_fees_accrued,
+            Errors::InsufficientProtocolFees
+        );
+
+        let accounts = TransferChecked {
+            from: self.source.to_account_info(),
+            to: self.protocol_fee_vault.to_account_info(),
+            mint: self.mint_usdc.to_account_info(),
+            authority: self.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
+        transfer_checked(cpi_ctx, amount, self.mint_usdc.decimals)?;
+
+        //e Accrued share becomes a collected fee, withdrawable like the others
+        self.lending_pool.liquidation_fees_accrued -= amount;
+        self.lending_pool.liquidation_fees_collected += amount;
+
+        msg!(
+            "Liquidation fees settled: {} (still accrued: {}, available: {})",
+            amount,
+            self.lending_pool.liquidation_fees_accrued,
+            self.lending_pool.protocol_fees_available()
+        );
+        Ok(())
+    }
+}
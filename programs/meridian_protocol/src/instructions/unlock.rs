@@ -14,7 +14,12 @@ pub struct UnLockPool<'info> {
     pub authority: Signer<'info>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",authority.key().as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
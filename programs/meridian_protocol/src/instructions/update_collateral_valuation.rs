@@ -24,13 +24,23 @@ pub struct UpdateCollateralValuation<'info> {
     pub mint_usdc: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",lending_pool.owner.as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
     #[account(
         mut,
-        seeds = [b"meridian_borrower_state", borrower.key().as_ref()],
+seeds = [
+            b"meridian_borrower_state",
+            lending_pool.key().as_ref(),
+            borrower.key().as_ref(),
+            &borrower_state.loan_id.to_le_bytes()
+        ],
         bump = borrower_state.bump_borrower_state
     )]
     pub borrower_state: Box<Account<'info, LoanState>>,
@@ -1,5 +1,9 @@
 use crate::errors::Errors;
-use crate::states::{AdminRegistry, LendingPool, LoanState};
+use crate::events::{AttestationSubmitted, VerificationDecided};
+use crate::states::{
+    AdminRegistry, AssayReport, Attestation, LendingPool, LoanState, QuorumOutcome, VerificationRequest,
+    VerificationStatus,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::Token;
@@ -19,14 +23,19 @@ pub struct Verify_asset<'info> {
     pub mint_usdc: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",lending_pool.owner.as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
     #[account(
         mut,
         associated_token::mint = mint_usdc,
-        associated_token::authority = lending_pool.owner,
+        associated_token::authority = lending_pool,
         associated_token::token_program = token_program,
     )]
     pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
@@ -38,10 +47,21 @@ pub struct Verify_asset<'info> {
     pub admin_registry: Box<Account<'info, AdminRegistry>>,
     #[account(
         mut,
-        seeds = [b"meridian_borrower_state", borrower_state.borrower.key().as_ref()],
+seeds = [
+            b"meridian_borrower_state",
+            lending_pool.key().as_ref(),
+            borrower_state.borrower.key().as_ref(),
+            &borrower_state.loan_id.to_le_bytes()
+        ],
         bump = borrower_state.bump_borrower_state
     )]
     pub borrower_state: Box<Account<'info, LoanState>>,
+    #[account(
+        mut,
+        seeds = [b"meridian_verification_request", borrower_state.key().as_ref()],
+        bump = verification_request.bump
+    )]
+    pub verification_request: Box<Account<'info, VerificationRequest>>,
     #[account(
         mut,
         associated_token::mint = mint_usdc,
@@ -49,8 +69,8 @@ pub struct Verify_asset<'info> {
         associated_token::token_program = token_program,
     )]
     pub borrower_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
-    ///CHECK: Safe,Will be created on the client side
-    #[account(mut)]
+    ///CHECK: The asset pledged to this loan
+    #[account(mut, address = borrower_state.nft_mint)]
     pub rwa_asset: UncheckedAccount<'info>,
     ///CHECK:
     #[account(
@@ -67,32 +87,144 @@ pub struct Verify_asset<'info> {
 }
 
 impl<'info> Verify_asset<'info> {
+    //e Admin picks up a pending request, the borrower can't cancel it from here on
+    pub fn start_review(&mut self) -> Result<()> {
+        require!(
+            self.admin_registry.is_admin(self.signer.key()),
+            Errors::OnlyAdmin
+        );
+        self.verification_request
+            .transition(VerificationStatus::UnderReview, Clock::get()?.unix_timestamp)?;
+
+        msg!(
+            "Verification {} under review by {}",
+            self.verification_request.verification_id,
+            self.signer.key()
+        );
+        Ok(())
+    }
+
     pub fn verify_asset(
         &mut self,
         verification_id: u32,
         is_verified: bool,
         purity_in_bps: u16,
         weight_in_grams: i64,
+        report: AssayReport,
     ) -> Result<()> {
         require!(
             self.admin_registry.is_admin(self.signer.key()),
             Errors::OnlyAdmin
         );
         require!(
-            self.borrower_state.verification_id == verification_id,
+            self.verification_request.verification_id == verification_id,
             Errors::AssetNotVerified
         );
-        self.borrower_state.is_verified = is_verified;
 
-        if is_verified {
-            self.borrower_state.is_rejected = false;
-        } else {
-            self.borrower_state.is_rejected = true;
+        require!(
+            weight_in_grams > 0 && purity_in_bps <= 10_000,
+            Errors::InvalidAssayResult
+        );
+
+        let current_time = Clock::get()?.unix_timestamp;
+        report.validate(current_time)?;
+        //e Attesting to a pending request counts as picking it up
+        if self.verification_request.status == VerificationStatus::Pending {
+            self.verification_request
+                .transition(VerificationStatus::UnderReview, current_time)?;
         }
+        require!(
+            self.verification_request.status == VerificationStatus::UnderReview,
+            Errors::InvalidVerificationTransition
+        );
 
-        self.borrower_state.weight_in_grams = weight_in_grams;
-        self.borrower_state.purity_in_bps = purity_in_bps;
-        self.borrower_state.is_verified = true;
+        //e Each admin attests once, the request is only decided once the pool quorum agrees
+        let quorum = self.lending_pool.verification_quorum;
+        let tolerance_bps = self.lending_pool.attestation_tolerance_bps;
+        let request_key = self.verification_request.key();
+        let lending_pool_key = self.lending_pool.key();
+        let request = &mut self.verification_request;
+        let attestation = Attestation {
+            verifier: self.signer.key(),
+            approve: is_verified,
+            weight_in_grams,
+            purity_in_bps,
+            attested_at: current_time,
+            conflicting: false,
+            report,
+        };
+        let conflicting = request.record_attestation(&attestation, tolerance_bps)?;
+        emit!(AttestationSubmitted {
+            lending_pool: lending_pool_key,
+            verification_request: request_key,
+            verification_id,
+            verifier: attestation.verifier,
+            approve: is_verified,
+            weight_in_grams,
+            purity_in_bps,
+            conflicting,
+            report: attestation.report.clone(),
+            attested_at: current_time,
+        });
+        if conflicting {
+            msg!(
+                "Attestation by {} conflicts with an earlier one, {} conflicts on verification {}",
+                self.signer.key(),
+                request.conflict_count,
+                verification_id
+            );
+        }
+
+        match request.quorum_outcome(quorum, tolerance_bps) {
+            Some(QuorumOutcome::Approved {
+                weight_in_grams,
+                purity_in_bps,
+            }) => {
+                request.reviewer = self.signer.key();
+                request.weight_in_grams = weight_in_grams;
+                request.purity_in_bps = purity_in_bps;
+                request.report = attestation.report.clone();
+                request.transition(VerificationStatus::Approved, current_time)?;
+                emit!(VerificationDecided {
+                    lending_pool: lending_pool_key,
+                    verification_request: request_key,
+                    verification_id,
+                    approved: true,
+                    weight_in_grams,
+                    purity_in_bps,
+                    report: attestation.report,
+                    decided_at: current_time,
+                });
+                //e Only an approved assay values the collateral
+                self.borrower_state.weight_in_grams = weight_in_grams;
+                self.borrower_state.purity_in_bps = purity_in_bps;
+                msg!("Verification {} approved", verification_id);
+            }
+            Some(QuorumOutcome::Rejected) => {
+                request.reviewer = self.signer.key();
+                request.report = attestation.report.clone();
+                request.transition(VerificationStatus::Rejected, current_time)?;
+                emit!(VerificationDecided {
+                    lending_pool: lending_pool_key,
+                    verification_request: request_key,
+                    verification_id,
+                    approved: false,
+                    weight_in_grams: 0,
+                    purity_in_bps: 0,
+                    report: attestation.report,
+                    decided_at: current_time,
+                });
+                msg!("Verification {} rejected", verification_id);
+            }
+            None => {
+                msg!(
+                    "Verification {}: {} attestations, quorum is {}",
+                    verification_id,
+                    request.attestation_count,
+                    quorum
+                );
+            }
+        }
         Ok(())
     }
 }
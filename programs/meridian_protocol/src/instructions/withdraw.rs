@@ -1,5 +1,6 @@
 use crate::errors::Errors;
-use crate::states::{AdminRegistry, Lender, LendingPool};
+use crate::math::{mul_div, mul_div_ceil, to_u64, BPS, WAD};
+use crate::states::{AdminRegistry, Lender, LendingPool, RateModel};
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token::Token;
@@ -19,14 +20,28 @@ pub struct Withdraw<'info> {
     pub lender: Signer<'info>,
     #[account(mut)]
     pub mint: Box<InterfaceAccount<'info, Mint>>,
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds = [b"meridian_lp_mint", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_lp_mint
+    )]
     pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
     #[account(
         mut,
-        seeds = [b"meridian_pool",lending_pool.owner.as_ref()],
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
         bump = lending_pool.bump_lending_pool
     )]
     pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
     #[account(
         mut,
         associated_token::mint = mint,
@@ -35,11 +50,15 @@ pub struct Withdraw<'info> {
     )]
     pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(
-        init_if_needed,
-        payer = lender,
-        space = 8 + Lender::INIT_SPACE,
-        seeds = [b"lender_seed", lender.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"meridian_fee_vault", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_fee_vault
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"lender_seed", lending_pool.key().as_ref(), lender.key().as_ref()],
+        bump = lender_state.bump
     )]
     pub lender_state: Box<Account<'info, Lender>>,
     #[account(
@@ -69,12 +88,95 @@ pub struct Withdraw<'info> {
 }
 
 impl<'info> Withdraw<'info> {
-    pub fn withdraw_liquidity(&mut self) -> Result<()> {
+    //e Burn a number of lp shares, reverts if the usdc received is below min_amount_out
+    pub fn withdraw_shares(&mut self, lp_shares: u64, min_amount_out: u64) -> Result<()> {
+        self.validate_withdrawal(lp_shares)?;
+        self.lending_pool
+            .accrue_interest(&self.rate_model, Clock::get()?.unix_timestamp)?;
+
+        let withdrawable_amount = self.get_total_withdrawable_amount(lp_shares)?;
+
+        //e Only the shares coming out of lots still inside their epoch pay the early fee
+        let matured_shares = self.get_matured_shares()?;
+        let early_shares = lp_shares.saturating_sub(matured_shares);
+        let early_amount = to_u64(mul_div(
+            withdrawable_amount as u128,
+            early_shares as u128,
+            lp_shares as u128,
+        )?)?;
+        let early_withdrawal_fee = self.calculate_early_withdrawal_fee(early_amount);
+        let amount_to_withdraw = withdrawable_amount - early_withdrawal_fee;
+
+        require!(amount_to_withdraw >= min_amount_out, Errors::SlippageExceeded);
+
+        self.process_withdrawal(lp_shares, amount_to_withdraw, early_withdrawal_fee)
+    }
+
+    //e Receive exactly amount_out usdc, reverts if it costs more than max_lp_shares
+    pub fn withdraw_exact_usdc(&mut self, amount_out: u64, max_lp_shares: u64) -> Result<()> {
+        require!(amount_out > 0, Errors::NullWithdrawalNotAllowed);
+        self.lending_pool
+            .accrue_interest(&self.rate_model, Clock::get()?.unix_timestamp)?;
+
+        let exchange_rate = self.lending_pool.exchange_rate()?;
+        let matured_shares = self.get_matured_shares()?;
+        let matured_amount = self.get_total_withdrawable_amount(matured_shares)?;
+
+        //e Matured lots are used first, whatever is left is grossed up by the early fee. Shares round up
+        let (lp_shares, early_withdrawal_fee) = if amount_out <= matured_amount {
+            (to_u64(mul_div_ceil(amount_out as u128, WAD, exchange_rate)?)?, 0)
+        } else {
+            let early_amount_out = amount_out - matured_amount;
+            let fee_bps = self.lending_pool.early_withdrawal_fee_bps as u128;
+            let early_amount = mul_div_ceil(early_amount_out as u128, BPS, BPS - fee_bps)?;
+            (
+                matured_shares + to_u64(mul_div_ceil(early_amount, WAD, exchange_rate)?)?,
+                to_u64(early_amount)? - early_amount_out,
+            )
+        };
+
+        require!(lp_shares <= max_lp_shares, Errors::SlippageExceeded);
+        self.validate_withdrawal(lp_shares)?;
+
+        self.process_withdrawal(lp_shares, amount_out, early_withdrawal_fee)
+    }
+
+    fn validate_withdrawal(&mut self, lp_shares: u64) -> Result<()> {
         require!(
             self.lender.key() == self.lender_state.owner,
             Errors::InvalidUser
         );
-        require!(self.lending_pool.is_locked == false, Errors::PoolLocked);
+        require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        require!(lp_shares > 0, Errors::NullWithdrawalNotAllowed);
+        require!(
+            lp_shares <= self.lender_state.lp_shares,
+            Errors::InsufficientShares
+        );
+        Ok(())
+    }
+
+    pub fn get_matured_shares(&mut self) -> Result<u64> {
+        let current_time = Clock::get()?.unix_timestamp;
+        Ok(self
+            .lender_state
+            .matured_shares(current_time, self.lending_pool.withdrawal_epoch))
+    }
+
+    fn process_withdrawal(
+        &mut self,
+        shares_to_burn: u64,
+        amount_to_withdraw: u64,
+        early_withdrawal_fee: u64,
+    ) -> Result<()> {
+        //e Instant withdrawals can't dip into liquidity reserved for the withdrawal queue
+        require!(
+            amount_to_withdraw + early_withdrawal_fee <= self.lending_pool.available_liquidity()?,
+            Errors::InsufficientLiquidityToWithdraw
+        );
 
         //BURN SHARES..
         let burn_accounts = Burn {
@@ -85,8 +187,6 @@ impl<'info> Withdraw<'info> {
 
         let cpi_program = self.token_program.to_account_info();
 
-        let shares_to_burn = self.lender_state.lp_shares;
-
         let cpi_ctx = CpiContext::new(cpi_program, burn_accounts);
 
         burn(cpi_ctx, shares_to_burn)?;
@@ -94,17 +194,6 @@ impl<'info> Withdraw<'info> {
         msg!("Shares burnt: {}", shares_to_burn);
 
         //WITHDRAWING AMOUNT
-        let amount_to_withdraw: u64;
-        let current_time = Clock::get()?.unix_timestamp;
-
-        let withdrawable_amount = self.get_total_withdrawable_amount(self.lender_state.lp_shares);
-
-        if self.is_withdrawal_epoch_over(self.lender_state.deposited_at, current_time) {
-            amount_to_withdraw = withdrawable_amount;
-        } else {
-            amount_to_withdraw = self.calculate_early_withdrawal_amount(withdrawable_amount);
-        }
-
         let accounts = TransferChecked {
             from: self.lending_pool_usdc_ata.to_account_info(),
             to: self.lender_usdc_ata.to_account_info(),
@@ -113,9 +202,13 @@ impl<'info> Withdraw<'info> {
         };
 
         let lending_pool_owner = self.lending_pool.owner;
+        let usdc_mint = self.lending_pool.usdc_mint;
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
         let seeds = &[
             &b"meridian_pool"[..],
             lending_pool_owner.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
             &[self.lending_pool.bump_lending_pool],
         ];
 
@@ -128,53 +221,68 @@ impl<'info> Withdraw<'info> {
         );
         transfer_checked(cpi_ctx, amount_to_withdraw, self.mint.decimals)?;
 
-        msg!("Liquidity Amount Withdrawn By: {}", self.lender.key());
-
-        Ok(())
-    }
+        //e Early withdrawal fee goes to the protocol fee vault
+        if early_withdrawal_fee > 0 {
+            let accounts = TransferChecked {
+                from: self.lending_pool_usdc_ata.to_account_info(),
+                to: self.protocol_fee_vault.to_account_info(),
+                mint: self.mint.to_account_info(),
+                authority: self.lending_pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                accounts,
+                signer_seeds,
+            );
+            transfer_checked(cpi_ctx, early_withdrawal_fee, self.mint.decimals)?;
+            self.lending_pool.early_withdrawal_fees_collected += early_withdrawal_fee;
+        }
 
-    pub fn is_withdrawal_epoch_over(&mut self, deposited_at: i64, current_time: i64) -> bool {
+        //e Shares leave the lots FIFO, taking their cost basis with them
+        let current_time = Clock::get()?.unix_timestamp;
         let withdrawal_epoch = self.lending_pool.withdrawal_epoch;
-        //Is it correct...?
-        if current_time - deposited_at >= withdrawal_epoch {
-            return true;
-        } else {
-            return false;
-        }
-    }
+        let cost_basis_withdrawn = self
+            .lender_state
+            .consume_shares(shares_to_burn, current_time, withdrawal_epoch)?
+            .cost_basis;
 
-    pub fn calculate_shares_to_mint(&mut self, deposit_amount: u64) -> u64 {
-        let lp_supply = self.lending_pool.lp_total_supply;
-        let total_liquidity_in_pool = self.lending_pool.total_deposited_usdc;
+        self.lending_pool.lp_total_supply -= shares_to_burn;
+        self.lending_pool.total_idle_usdc -= amount_to_withdraw + early_withdrawal_fee;
+        self.lending_pool.total_deposited_usdc = self
+            .lending_pool
+            .total_deposited_usdc
+            .saturating_sub(cost_basis_withdrawn);
 
-        if lp_supply == 0 {
-            return deposit_amount;
-        };
+        msg!(
+            "Liquidity Amount Withdrawn By: {} : {} usdc for {} lp shares",
+            self.lender.key(),
+            amount_to_withdraw,
+            shares_to_burn
+        );
 
-        let shares_to_mint = deposit_amount * lp_supply / total_liquidity_in_pool;
-        return shares_to_mint;
+        Ok(())
     }
 
-    pub fn get_total_withdrawable_amount(&mut self, lp_shares: u64) -> u64 {
-        let total_liquidity = self.lending_pool.total_deposited_usdc;
-        let total_lp_supply = self.lending_pool.lp_total_supply;
-        let lp_share_price = total_liquidity / total_lp_supply as u64;
+    pub fn calculate_shares_to_mint(&mut self, deposit_amount: u64) -> Result<u64> {
+        self.lending_pool.shares_for_amount(deposit_amount)
+    }
 
-        let withdrawable_amount = lp_shares * lp_share_price;
-        return withdrawable_amount;
+    pub fn get_total_withdrawable_amount(&mut self, lp_shares: u64) -> Result<u64> {
+        self.lending_pool.amount_for_shares(lp_shares)
     }
 
     pub fn get_total_interest_earned(
         &mut self,
         total_deposited_collateral: u64,
         total_lp_shares_owned: u64,
-    ) -> u64 {
-        let total_withdrawable_amount = self.get_total_withdrawable_amount(total_lp_shares_owned);
-        let interest_earned = total_withdrawable_amount - total_deposited_collateral;
-        return interest_earned;
+    ) -> Result<u64> {
+        let total_withdrawable_amount = self.get_total_withdrawable_amount(total_lp_shares_owned)?;
+        let interest_earned = total_withdrawable_amount.saturating_sub(total_deposited_collateral);
+        Ok(interest_earned)
     }
 
-    pub fn calculate_early_withdrawal_amount(&mut self, total_withdrawal_amount: u64) -> u64 {
-        return total_withdrawal_amount * self.lending_pool.early_withdrawal_fee_bps as u64 / 10000;
+    //e Fee charged on the part of a withdrawal that is still inside its epoch
+    pub fn calculate_early_withdrawal_fee(&mut self, early_withdrawal_amount: u64) -> u64 {
+        return early_withdrawal_amount * self.lending_pool.early_withdrawal_fee_bps as u64 / 10000;
     }
 }
@@ -0,0 +1,93 @@
+use crate::errors::Errors;
+use crate::states::LendingPool;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked};
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Owner sweeps collected protocol fees out of the fee vault to any usdc account
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(address = lending_pool.usdc_mint)]
+    pub mint_usdc: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        mut,
+        seeds = [b"meridian_fee_vault", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_fee_vault
+    )]
+    pub protocol_fee_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::mint = mint_usdc,
+        token::token_program = token_program,
+    )]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawProtocolFees<'info> {
+    pub fn withdraw_protocol_fees(&mut self, amount: u64) -> Result<()> {
+        require!(
+            self.authority.key() == self.lending_pool.owner.key(),
+            Errors::OnlyAuthority
+        );
+        require!(amount > 0, Errors::NullWithdrawalNotAllowed);
+        require!(
+            amount <= self.lending_pool.protocol_fees_available(),
+            Errors::InsufficientProtocolFees
+        );
+
+        let accounts = TransferChecked {
+            from: self.protocol_fee_vault.to_account_info(),
+            to: self.destination.to_account_info(),
+            mint: self.mint_usdc.to_account_info(),
+            authority: self.lending_pool.to_account_info(),
+        };
+        let lending_pool_owner = self.lending_pool.owner.key();
+        let usdc_mint = self.lending_pool.usdc_mint.key();
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
+        let seeds: &[&[&[u8]]] = &[&[
+            b"meridian_pool",
+            lending_pool_owner.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
+            &[self.lending_pool.bump_lending_pool],
+        ]];
+
+        let cpi_ctx =
+            CpiContext::new_with_signer(self.token_program.to_account_info(), accounts, seeds);
+        transfer_checked(cpi_ctx, amount, self.mint_usdc.decimals)?;
+
+        self.lending_pool.protocol_fees_withdrawn += amount;
+
+        msg!(
+            "Protocol fees withdrawn: {} to {} (origination: {}, early withdrawal: {}, liquidation: {}, reserves: {}, left: {})",
+            amount,
+            self.destination.key(),
+            self.lending_pool.origination_fees_collected,
+            self.lending_pool.early_withdrawal_fees_collected,
+            self.lending_pool.liquidation_fees_collected,
+            self.lending_pool.reserve_fees_collected,
+            self.lending_pool.protocol_fees_available()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+use crate::errors::Errors;
+use crate::states::{Lender, LendingPool, RateModel, WithdrawalRequest};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use anchor_spl::token_interface::{
+    burn, transfer_checked, Burn, Mint, TokenAccount, TransferChecked,
+};
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Phase 1: lender escrows lp shares into the pool lp vault and joins the queue
+#[derive(Accounts)]
+pub struct RequestWithdrawal<'info> {
+    #[account(mut)]
+    pub lender: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"meridian_lp_mint", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_lp_mint
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        mut,
+        seeds = [b"lender_seed", lending_pool.key().as_ref(), lender.key().as_ref()],
+        bump = lender_state.bump
+    )]
+    pub lender_state: Box<Account<'info, Lender>>,
+    #[account(
+        init,
+        payer = lender,
+        space = 8 + WithdrawalRequest::INIT_SPACE,
+        seeds = [
+            b"meridian_withdrawal_request",
+            lending_pool.key().as_ref(),
+            &lending_pool.withdrawal_queue_tail.to_le_bytes()
+        ],
+        bump
+    )]
+    pub withdrawal_request: Box<Account<'info, WithdrawalRequest>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = lending_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub lending_pool_lp_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = lender,
+        associated_token::token_program = token_program,
+    )]
+    pub lender_lp_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RequestWithdrawal<'info> {
+    pub fn request_withdrawal(&mut self, lp_shares: u64, bumps: &RequestWithdrawalBumps) -> Result<()> {
+        require!(
+            self.lender.key() == self.lender_state.owner,
+            Errors::InvalidUser
+        );
+        require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        require!(lp_shares > 0, Errors::NullWithdrawalNotAllowed);
+        require!(
+            lp_shares <= self.lender_state.lp_shares,
+            Errors::InsufficientShares
+        );
+
+        //ESCROW SHARES IN THE POOL LP VAULT
+        let accounts = TransferChecked {
+            from: self.lender_lp_ata.to_account_info(),
+            to: self.lending_pool_lp_ata.to_account_info(),
+            mint: self.mint_lp.to_account_info(),
+            authority: self.lender.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), accounts);
+        transfer_checked(cpi_ctx, lp_shares, self.mint_lp.decimals)?;
+
+        //e Shares leave the lots FIFO, their cost basis follows them into the request.
+        //e The request waits out a full epoch, so no early withdrawal fee applies
+        let current_time = Clock::get()?.unix_timestamp;
+        let withdrawal_epoch = self.lending_pool.withdrawal_epoch;
+        let cost_basis = self
+            .lender_state
+            .consume_shares(lp_shares, current_time, withdrawal_epoch)?
+            .cost_basis;
+
+        let request = &mut self.withdrawal_request;
+        request.lending_pool = self.lending_pool.key();
+        request.owner = self.lender.key();
+        request.request_id = self.lending_pool.withdrawal_queue_tail;
+        request.lp_shares = lp_shares;
+        request.cost_basis = cost_basis;
+        request.requested_at = current_time;
+        request.claimable_at = current_time + self.lending_pool.withdrawal_epoch;
+        request.bump = bumps.withdrawal_request;
+
+        self.lending_pool.withdrawal_queue_tail += 1;
+        self.lending_pool.total_queued_shares += lp_shares;
+
+        msg!(
+            "Withdrawal request {} queued for {} lp shares, claimable at {}",
+            request.request_id,
+            lp_shares,
+            request.claimable_at
+        );
+        Ok(())
+    }
+}
+
+//e Phase 2: once the epoch is over the head of the queue is paid out against idle liquidity.
+//e Anyone can crank the head so an absent lender can't block the queue, funds always go to the owner
+#[derive(Accounts)]
+pub struct ClaimWithdrawal<'info> {
+    #[account(mut)]
+    pub claimer: Signer<'info>,
+    ///CHECK: Owner of the withdrawal request, receives the usdc and the request rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [b"meridian_lp_mint", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_lp_mint
+    )]
+    pub mint_lp: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        seeds = [
+            b"meridian_pool",
+            lending_pool.owner.as_ref(),
+            lending_pool.usdc_mint.as_ref(),
+            &lending_pool.pool_id.to_le_bytes()
+        ],
+        bump = lending_pool.bump_lending_pool
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+    #[account(
+        seeds = [b"meridian_rate_model", lending_pool.key().as_ref()],
+        bump = lending_pool.bump_rate_model
+    )]
+    pub rate_model: Box<Account<'info, RateModel>>,
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        seeds = [
+            b"meridian_withdrawal_request",
+            lending_pool.key().as_ref(),
+            &withdrawal_request.request_id.to_le_bytes()
+        ],
+        bump = withdrawal_request.bump
+    )]
+    pub withdrawal_request: Box<Account<'info, WithdrawalRequest>>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = lending_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub lending_pool_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = lending_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub lending_pool_lp_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = claimer,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_usdc_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ClaimWithdrawal<'info> {
+    pub fn claim_withdrawal(&mut self) -> Result<()> {
+        require!(!self.lending_pool.is_locked, Errors::PoolLocked);
+        require!(
+            !self.lending_pool.flash_loan_active,
+            Errors::FlashLoanActive
+        );
+        require!(
+            self.withdrawal_request.request_id == self.lending_pool.withdrawal_queue_head,
+            Errors::NotHeadOfWithdrawalQueue
+        );
+        require!(
+            Clock::get()?.unix_timestamp >= self.withdrawal_request.claimable_at,
+            Errors::WithdrawalEpochNotOver
+        );
+
+        self.lending_pool
+            .accrue_interest(&self.rate_model, Clock::get()?.unix_timestamp)?;
+
+        let lp_shares = self.withdrawal_request.lp_shares;
+        let amount_to_withdraw = self.lending_pool.amount_for_shares(lp_shares)?;
+
+        require!(
+            amount_to_withdraw <= self.lending_pool.total_idle_usdc,
+            Errors::InsufficientLiquidityToWithdraw
+        );
+
+        let lending_pool_owner = self.lending_pool.owner;
+        let usdc_mint = self.lending_pool.usdc_mint;
+        let pool_id = self.lending_pool.pool_id.to_le_bytes();
+        let seeds = &[
+            &b"meridian_pool"[..],
+            lending_pool_owner.as_ref(),
+            usdc_mint.as_ref(),
+            pool_id.as_ref(),
+            &[self.lending_pool.bump_lending_pool],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        //BURN ESCROWED SHARES..
+        let burn_accounts = Burn {
+            mint: self.mint_lp.to_account_info(),
+            from: self.lending_pool_lp_ata.to_account_info(),
+            authority: self.lending_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            burn_accounts,
+            signer_seeds,
+        );
+        burn(cpi_ctx, lp_shares)?;
+
+        //PAYING OUT THE OWNER
+        let accounts = TransferChecked {
+            from: self.lending_pool_usdc_ata.to_account_info(),
+            to: self.owner_usdc_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            authority: self.lending_pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            accounts,
+            signer_seeds,
+        );
+        transfer_checked(cpi_ctx, amount_to_withdraw, self.mint.decimals)?;
+
+        self.lending_pool.lp_total_supply -= lp_shares;
+        self.lending_pool.total_queued_shares -= lp_shares;
+        self.lending_pool.total_idle_usdc -= amount_to_withdraw;
+        self.lending_pool.total_deposited_usdc = self
+            .lending_pool
+            .total_deposited_usdc
+            .saturating_sub(self.withdrawal_request.cost_basis);
+        self.lending_pool.withdrawal_queue_head += 1;
+
+        msg!(
+            "Withdrawal request {} claimed: {} usdc for {} lp shares",
+            self.withdrawal_request.request_id,
+            amount_to_withdraw,
+            lp_shares
+        );
+        Ok(())
+    }
+}
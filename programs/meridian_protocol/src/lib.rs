@@ -1,6 +1,8 @@
 pub mod constants;
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod math;
 pub mod states;
 use anchor_lang::prelude::*;
 pub use instructions::mock_oracle;
@@ -16,6 +18,7 @@ pub mod meridian_protocol {
 
     pub fn initialize(
         ctx: Context<Initialize>,
+        pool_id: u64,
         loan_to_value_bps: u16,
         utilization_rate_tier_1_bps: u16,
         utilization_rate_tier_2_bps: u16,
@@ -35,6 +38,7 @@ pub mod meridian_protocol {
         liquidator_reward_bps: u16,
     ) -> Result<()> {
         ctx.accounts.initialize_pool(
+            pool_id,
             loan_to_value_bps,
             utilization_rate_tier_1_bps,
             utilization_rate_tier_2_bps,
@@ -67,6 +71,111 @@ pub mod meridian_protocol {
         Ok(())
     }
 
+    pub fn set_borrow_limits(
+        ctx: Context<ConfigurePool>,
+        reserve_ratio_bps: u16,
+        max_utilization_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_borrow_limits(reserve_ratio_bps, max_utilization_bps)?;
+        Ok(())
+    }
+
+    pub fn set_interest_mode(
+        ctx: Context<ConfigurePool>,
+        interest_mode: u8,
+        compounding_period: i64,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_interest_mode(interest_mode, compounding_period)?;
+        Ok(())
+    }
+
+    pub fn set_rate_model(
+        ctx: Context<ConfigurePool>,
+        model_type: u8,
+        base_rate_bps: u16,
+        slope_1_bps: u16,
+        slope_2_bps: u16,
+        optimal_utilization_bps: u16,
+        points: Vec<RatePoint>,
+    ) -> Result<()> {
+        ctx.accounts.set_rate_model(
+            model_type,
+            base_rate_bps,
+            slope_1_bps,
+            slope_2_bps,
+            optimal_utilization_bps,
+            points,
+        )?;
+        Ok(())
+    }
+
+    pub fn set_term_params(
+        ctx: Context<ConfigurePool>,
+        grace_period: i64,
+        penalty_apr_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set_term_params(grace_period, penalty_apr_bps)?;
+        Ok(())
+    }
+
+    pub fn set_installment_params(
+        ctx: Context<ConfigurePool>,
+        late_fee_bps: u16,
+        max_missed_installments: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_installment_params(late_fee_bps, max_missed_installments)?;
+        Ok(())
+    }
+
+    pub fn set_verification_sla(ctx: Context<ConfigurePool>, verification_sla: i64) -> Result<()> {
+        ctx.accounts.set_verification_sla(verification_sla)?;
+        Ok(())
+    }
+
+    pub fn set_verification_quorum(
+        ctx: Context<ConfigurePool>,
+        verification_quorum: u8,
+        attestation_tolerance_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts
+            .set_verification_quorum(verification_quorum, attestation_tolerance_bps)?;
+        Ok(())
+    }
+
+    pub fn set_flash_fee(ctx: Context<ConfigurePool>, flash_fee_bps: u16) -> Result<()> {
+        ctx.accounts.set_flash_fee(flash_fee_bps)?;
+        Ok(())
+    }
+
+    pub fn set_origination_fee_mode(
+        ctx: Context<ConfigurePool>,
+        origination_fee_mode: u8,
+    ) -> Result<()> {
+        ctx.accounts.set_origination_fee_mode(origination_fee_mode)?;
+        Ok(())
+    }
+
+    pub fn set_reserve_factor(ctx: Context<ConfigurePool>, reserve_factor_bps: u16) -> Result<()> {
+        ctx.accounts.set_reserve_factor(reserve_factor_bps)?;
+        Ok(())
+    }
+
+    pub fn set_refinance_fee(ctx: Context<ConfigurePool>, refinance_fee_bps: u16) -> Result<()> {
+        ctx.accounts.set_refinance_fee(refinance_fee_bps)?;
+        Ok(())
+    }
+
+    pub fn set_fixed_rate_premium(
+        ctx: Context<ConfigurePool>,
+        fixed_rate_premium_bps: u16,
+    ) -> Result<()> {
+        ctx.accounts.set_fixed_rate_premium(fixed_rate_premium_bps)?;
+        Ok(())
+    }
+
     pub fn add_admin(ctx: Context<AddAdmin>, admin: Pubkey) -> Result<()> {
         ctx.accounts.add_admin(admin)?;
         Ok(())
@@ -96,7 +205,7 @@ pub mod meridian_protocol {
     }
 
     pub fn amount_to_shares(ctx: Context<Lending>, deposit_amount: u64) -> Result<u64> {
-        let amount = ctx.accounts.calculate_shares_to_mint(deposit_amount);
+        let amount = ctx.accounts.calculate_shares_to_mint(deposit_amount)?;
 
         msg!(
             "If you deposit {} then you receive {} amount of shares",
@@ -108,12 +217,12 @@ pub mod meridian_protocol {
 
     //LENDER OPERATIONS
     pub fn deposit(ctx: Context<Lending>, deposit_amount: u64) -> Result<()> {
-        ctx.accounts.deposit_liquidity(deposit_amount)?;
+        ctx.accounts.deposit_liquidity(deposit_amount, &ctx.bumps)?;
         Ok(())
     }
 
     pub fn calculate_withdrawable_amount(ctx: Context<Withdraw>, lp_shares: u64) -> Result<u64> {
-        let withdrawable_shares = ctx.accounts.get_total_withdrawable_amount(lp_shares);
+        let withdrawable_shares = ctx.accounts.get_total_withdrawable_amount(lp_shares)?;
         msg!(
             "You can withdraw {} usdc for {} amount of lp shares",
             withdrawable_shares,
@@ -122,8 +231,21 @@ pub mod meridian_protocol {
         Ok(withdrawable_shares)
     }
 
-    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
-        ctx.accounts.withdraw_liquidity()?;
+    pub fn withdraw(ctx: Context<Withdraw>, lp_shares: u64, min_amount_out: u64) -> Result<()> {
+        ctx.accounts.withdraw_shares(lp_shares, min_amount_out)?;
+        msg!(
+            "Liquidity Withdrawn by Lender: {}",
+            ctx.accounts.lender.key()
+        );
+        Ok(())
+    }
+
+    pub fn withdraw_exact_usdc(
+        ctx: Context<Withdraw>,
+        amount_out: u64,
+        max_lp_shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw_exact_usdc(amount_out, max_lp_shares)?;
         msg!(
             "Liquidity Withdrawn by Lender: {}",
             ctx.accounts.lender.key()
@@ -131,20 +253,75 @@ pub mod meridian_protocol {
         Ok(())
     }
 
+    pub fn request_withdrawal(ctx: Context<RequestWithdrawal>, lp_shares: u64) -> Result<()> {
+        ctx.accounts.request_withdrawal(lp_shares, &ctx.bumps)?;
+        Ok(())
+    }
+
+    pub fn claim_withdrawal(ctx: Context<ClaimWithdrawal>) -> Result<()> {
+        ctx.accounts.claim_withdrawal()?;
+        Ok(())
+    }
+
     pub fn get_total_interest_earned(
         ctx: Context<Withdraw>,
         total_deposited_collateral: u64,
         total_lp_shares_owned: u64,
-    ) -> Result<()> {
+    ) -> Result<u64> {
         let earned = ctx
             .accounts
-            .get_total_interest_earned(total_deposited_collateral, total_lp_shares_owned);
+            .get_total_interest_earned(total_deposited_collateral, total_lp_shares_owned)?;
         msg!("You've earned: {} interest on your collateral", earned);
+        Ok(earned)
+    }
+
+    pub fn withdraw_protocol_fees(ctx: Context<WithdrawProtocolFees>, amount: u64) -> Result<()> {
+        ctx.accounts.withdraw_protocol_fees(amount)?;
         Ok(())
     }
 
+    pub fn settle_liquidation_fees(ctx: Context<SettleLiquidationFees>, amount: u64) -> Result<()> {
+        ctx.accounts.settle_liquidation_fees(amount)?;
+        Ok(())
+    }
+
+    pub fn accrue_interest(ctx: Context<AccrueInterest>) -> Result<()> {
+        ctx.accounts.accrue_interest()?;
+        Ok(())
+    }
+
+    pub fn get_rates(ctx: Context<PoolInfo>) -> Result<RateQuote> {
+        let rates = ctx.accounts.get_rates()?;
+        msg!(
+            "Utilization: {} bps, Borrow APR: {} bps, Supply APY: {} bps",
+            rates.utilization_bps,
+            rates.borrow_apr_bps,
+            rates.supply_apy_bps
+        );
+        Ok(rates)
+    }
+
+    pub fn get_protocol_fees(ctx: Context<PoolInfo>) -> Result<ProtocolFees> {
+        let fees = ctx.accounts.get_protocol_fees()?;
+        msg!(
+            "Reserve factor: {} bps, Reserves: {}, Fees available: {}",
+            ctx.accounts.lending_pool.reserve_factor_bps,
+            fees.reserves,
+            fees.available
+        );
+        Ok(fees)
+    }
+
+    pub fn get_lp_exchange_rate(ctx: Context<PoolInfo>) -> Result<u128> {
+        let exchange_rate = ctx.accounts.get_exchange_rate()?;
+        msg!("1 LP share is worth {} usdc (scaled by 1e18)", exchange_rate);
+        Ok(exchange_rate)
+    }
+
     //Borrow functions
-    pub fn deposit_collateral_for_verification(ctx: Context<Borrow>) -> Result<()> {
+    pub fn deposit_collateral_for_verification(
+        ctx: Context<DepositForVerification>,
+    ) -> Result<()> {
         ctx.accounts.deposit_for_verification(&ctx.bumps)?;
         Ok(())
     }
@@ -156,15 +333,26 @@ pub mod meridian_protocol {
     }
 
     //ADMIN VERIFICATION..
+    pub fn start_verification_review(ctx: Context<Verify_asset>) -> Result<()> {
+        ctx.accounts.start_review()?;
+        Ok(())
+    }
+
     pub fn verify_asset(
         ctx: Context<Verify_asset>,
         verification_id: u32,
         is_verified: bool,
         purity_in_bps: u16,
         weight_in_grams: i64,
+        report: AssayReport,
     ) -> Result<()> {
-        ctx.accounts
-            .verify_asset(verification_id, is_verified, purity_in_bps, weight_in_grams)?;
+        ctx.accounts.verify_asset(
+            verification_id,
+            is_verified,
+            purity_in_bps,
+            weight_in_grams,
+            report,
+        )?;
         Ok(())
     }
 
@@ -174,8 +362,52 @@ pub mod meridian_protocol {
         Ok(())
     }
 
-    pub fn borrow_assets(ctx: Context<Borrow>) -> Result<()> {
-        ctx.accounts.borrow(false)?;
+    pub fn borrow_assets(
+        ctx: Context<Borrow>,
+        amount: u64,
+        rate_type: u8,
+        term_seconds: i64,
+        repayment_type: u8,
+        origination_fee_mode: Option<u8>,
+    ) -> Result<()> {
+        ctx.accounts.borrow(
+            amount,
+            rate_type,
+            term_seconds,
+            repayment_type,
+            origination_fee_mode,
+        )?;
+        Ok(())
+    }
+
+    pub fn draw_additional(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+        ctx.accounts.draw_additional(amount)?;
+        Ok(())
+    }
+
+    pub fn flash_borrow(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+        ctx.accounts.flash_borrow(amount)?;
+        Ok(())
+    }
+
+    pub fn flash_repay(ctx: Context<FlashLoan>) -> Result<()> {
+        ctx.accounts.flash_repay()?;
+        Ok(())
+    }
+
+    pub fn refinance_loan(
+        ctx: Context<Borrow>,
+        rate_type: u8,
+        term_seconds: i64,
+        repayment_type: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .refinance(rate_type, term_seconds, repayment_type)?;
+        Ok(())
+    }
+
+    pub fn cancel_verification(ctx: Context<Borrow>) -> Result<()> {
+        ctx.accounts.cancel_verification()?;
         Ok(())
     }
 
@@ -216,14 +448,27 @@ pub mod meridian_protocol {
     }
 
     
+    pub fn pay_installment(ctx: Context<Repay>) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        ctx.accounts.pay_installment(current_time)?;
+        Ok(())
+    }
+
     //GETTER FUNCTIONS FOR REPAY LEFT
-    pub fn total_debt_left(ctx: Context<Repay>,current_time: i64) -> Result<u64> {
+    pub fn total_debt_left(ctx: Context<Repay>) -> Result<u64> {
+        let current_time = Clock::get()?.unix_timestamp;
         let total_debt = ctx.accounts.total_debt_to_repay(current_time)?;
         msg!("Total debt to repay is: {}", total_debt);
 
         Ok(total_debt)
     }
 
+    //DEFAULTS
+    pub fn mark_defaulted(ctx: Context<MarkDefaulted>) -> Result<()> {
+        ctx.accounts.mark_defaulted()?;
+        Ok(())
+    }
+
     //LIQUIDATE
     pub fn liquidate(ctx: Context<Liquidate>) -> Result<()> {
         ctx.accounts.liquidate()?;
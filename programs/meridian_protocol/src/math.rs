@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::Errors;
+
+// ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
+// ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
+// ███▀████▀███ ███▄▄    ███▄▄███▀  ███  ███  ███  ███  ███  ███ ███▀██▄███
+// ███  ▀▀  ███ ███      ███▀▀██▄   ███  ███  ███  ███  ███▀▀███ ███  ▀████
+// ███      ███ ▀███████ ███  ▀███ ▄███▄ ██████▀  ▄███▄ ███  ███ ███    ███
+
+//e Fixed point helpers, every ratio in the protocol is scaled by WAD (10^18)
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+pub const BPS: u128 = 10_000;
+
+//e a * b / c, rounded down, with a u128 intermediate
+pub fn mul_div(a: u128, b: u128, c: u128) -> Result<u128> {
+    require!(c != 0, Errors::MathOverflow);
+    a.checked_mul(b)
+        .ok_or(error!(Errors::MathOverflow))?
+        .checked_div(c)
+        .ok_or(error!(Errors::MathOverflow))
+}
+
+//e a * b / c, rounded up, used whenever rounding must favour the pool
+pub fn mul_div_ceil(a: u128, b: u128, c: u128) -> Result<u128> {
+    require!(c != 0, Errors::MathOverflow);
+    let numerator = a.checked_mul(b).ok_or(error!(Errors::MathOverflow))?;
+    Ok(numerator
+        .checked_add(c - 1)
+        .ok_or(error!(Errors::MathOverflow))?
+        / c)
+}
+
+pub fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value).map_err(|_| error!(Errors::MathOverflow))
+}
+
+//e e^x - 1 for x scaled by WAD, taylor series summed until the terms vanish
+pub fn exp_minus_one_wad(x: u128) -> Result<u128> {
+    let mut term = x;
+    let mut sum = x;
+    let mut i: u128 = 2;
+    while term > 0 && i <= 32 {
+        term = mul_div(term, x, WAD * i)?;
+        sum = sum.checked_add(term).ok_or(error!(Errors::MathOverflow))?;
+        i += 1;
+    }
+    Ok(sum)
+}
+
+//e base^exp for base scaled by WAD, exponentiation by squaring
+pub fn pow_wad(base: u128, mut exp: u64) -> Result<u128> {
+    let mut result = WAD;
+    let mut base = base;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_div(result, base, WAD)?;
+        }
+        exp >>= 1;
+        if exp > 0 {
+            base = mul_div(base, base, WAD)?;
+        }
+    }
+    Ok(result)
+}
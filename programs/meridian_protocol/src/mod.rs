@@ -10,3 +10,6 @@ pub use errors::*;
 pub mod constants;
 pub use constants::*;
 
+pub mod math;
+pub use math::*;
+
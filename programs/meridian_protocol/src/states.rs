@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
 
+use crate::constants::{
+    INSTALLMENT_INTERVAL, INTEREST_MODE_COMPOUND_PERIODIC, INTEREST_MODE_COMPOUND_PER_SECOND,
+    MAX_ATTESTATIONS, MAX_BAR_SERIAL_LEN, MAX_RATE_POINTS, MAX_REFINERY_LEN, MAX_REPORT_URI_LEN, RATE_MODEL_KINKED, RATE_MODEL_PIECEWISE_LINEAR, RATE_TYPE_VARIABLE,
+    REPAYMENT_TYPE_INSTALLMENT, SECONDS_PER_YEAR,
+};
 use crate::errors::Errors;
+use crate::math::{exp_minus_one_wad, mul_div, pow_wad, to_u64, BPS, WAD};
 
 // ▄▄▄      ▄▄▄  ▄▄▄▄▄▄▄ ▄▄▄▄▄▄▄   ▄▄▄▄▄ ▄▄▄▄▄▄   ▄▄▄▄▄   ▄▄▄▄   ▄▄▄    ▄▄▄
 // ████▄  ▄████ ███▀▀▀▀▀ ███▀▀███▄  ███  ███▀▀██▄  ███  ▄██▀▀██▄ ████▄  ███
@@ -12,15 +18,48 @@ use crate::errors::Errors;
 #[derive(InitSpace)]
 pub struct LendingPool {
     pub owner: Pubkey, //AUTHORITY/OWNER OF THE LENDING POOL
+    pub pool_id: u64,  //POOL INDEX, AN OWNER CAN RUN SEVERAL POOLS PER STABLECOIN
     pub admin_registry: Pubkey,
-    pub total_deposited_usdc: u64,
-    pub total_borrowed: u64,
+    pub total_deposited_usdc: u64, //TOTAL USDC DEPOSITED BY LENDERS (COST BASIS, NET OF WITHDRAWALS)
+    pub total_borrowed: u64,       //OUTSTANDING PRINCIPAL LENT TO BORROWERS
+
+    //POOL ASSETS = IDLE CASH + OUTSTANDING PRINCIPAL + ACCRUED INTEREST - ACCRUED RESERVES - BAD DEBT
+    pub total_idle_usdc: u64,        //USDC SITTING IN THE POOL VAULT AVAILABLE TO BORROW/WITHDRAW
+    pub total_interest_accrued: u64, //INTEREST OWED BY BORROWERS, NOT YET PAID
+    pub total_reserves_accrued: u64, //RESERVE CUT OF THE INTEREST OWED, TAKEN WITH THE FACTOR IN EFFECT AS IT ACCRUED
+    pub total_bad_debt: u64,         //DEBT OF LIQUIDATED LOANS THAT WON'T BE REPAID IN USDC
+
+    //INTEREST ACCRUAL
+    pub borrow_index: u128,             //CUMULATIVE YEARS ELAPSED SINCE POOL CREATION, SCALED BY WAD
+    pub last_accrual_ts: i64,           //LAST TIME THE BORROW INDEX WAS MOVED FORWARD
+    pub total_weighted_debt: u128,      //SUM OF INTEREST BEARING DEBT * APR_BPS OVER ALL OPEN FIXED RATE LOANS
+    pub variable_rate_index: u128,      //CUMULATIVE VARIABLE APR * YEARS, SCALED BY WAD
+    pub total_variable_debt: u64,       //SUM OF INTEREST BEARING DEBT OVER ALL OPEN VARIABLE RATE LOANS
+    pub reserve_index: u128,            //BORROW INDEX WEIGHTED BY THE RESERVE FACTOR IN EFFECT, SCALED BY WAD * BPS
+    pub reserve_variable_index: u128,   //VARIABLE RATE INDEX WEIGHTED BY THE RESERVE FACTOR IN EFFECT, SCALED BY WAD * BPS
+    pub fixed_rate_premium_bps: u16,    //ADDED ON TOP OF THE CURRENT RATE FOR LOANS THAT LOCK THEIR RATE
+
+    //TERM LOANS
+    pub grace_period: i64,     //SECONDS AFTER MATURITY BEFORE AN UNPAID LOAN CAN BE MARKED DEFAULTED
+    pub penalty_apr_bps: u16,  //EXTRA APR ON THE PRINCIPAL FOR EVERY SECOND A LOAN RUNS PAST MATURITY
+
+    //INSTALLMENT LOANS
+    pub late_fee_bps: u16,            //CHARGED ON AN INSTALLMENT AMOUNT ONCE IT'S MISSED
+    pub max_missed_installments: u16, //OVERDUE INSTALLMENTS AT WHICH THE LOAN CAN BE MARKED DEFAULTED
+    pub interest_mode: u8,              //0 SIMPLE, 1 COMPOUND PER SECOND, 2 COMPOUND PER PERIOD
+    pub compounding_period: i64,        //SECONDS PER COMPOUNDING PERIOD, ONLY USED BY MODE 2
 
     pub protocol_admin_count: u8,
     pub withdrawal_epoch: i64,       //WITHDRAWAL EPOCH PERIOD
+    pub withdrawal_queue_head: u64,  //NEXT WITHDRAWAL REQUEST TO BE CLAIMED (FIFO)
+    pub withdrawal_queue_tail: u64,  //ID ASSIGNED TO THE NEXT WITHDRAWAL REQUEST
+    pub total_queued_shares: u64,    //LP SHARES ESCROWED IN THE WITHDRAWAL QUEUE
     pub lp_total_supply: u64,        //TOTAL LP SUPPLY/SHARES MINTED
     pub usdc_mint: Pubkey,           //MINT FOR THE USDC
-    pub protocol_usdc_vault: Pubkey, //VAULT FOR THE PROTOCOL USDC TREASURY
+    pub protocol_usdc_vault: Pubkey, //VAULT FOR THE PROTOCOL USDC TREASURY (OWNED BY THE POOL PDA)
+    pub protocol_lp_vault: Pubkey,   //VAULT FOR THE POOL'S LP TOKENS (OWNED BY THE POOL PDA)
+    pub lp_mint: Pubkey,             //LP MINT, MINT AUTHORITY IS THE POOL PDA
+    pub rate_model: Pubkey,          //RATE MODEL ACCOUNT PRICING BORROWS OFF UTILIZATION
     pub loan_to_value_bps: u16,
 
     //BUMP
@@ -28,6 +67,9 @@ pub struct LendingPool {
     pub bump_seize_vault: u8,
     pub bump_verification_vault: u8,
     pub bump_admin_registry: u8,
+    pub bump_lp_mint: u8,
+    pub bump_rate_model: u8,
+    pub bump_fee_vault: u8,
 
     //LIQUIDATION
     pub liquidation_threshold_bps: u16,
@@ -51,25 +93,472 @@ pub struct LendingPool {
     //FEES
     pub early_withdrawal_fee_bps: u16, //EARLY WITHDRAWAL FEE FOR THE LENDER (5%)..
     pub origination_fee_bps: u16,      //ORIGINATION FEE  (1% for the borrowers)..
+    pub refinance_fee_bps: u16,        //CHARGED ON THE OUTSTANDING DEBT WHEN A LOAN IS REFINANCED, 0 = FREE
+    pub reserve_factor_bps: u16,       //PROTOCOL CUT OF THE INTEREST BORROWERS PAY, THE REST GOES TO LPS
+    pub origination_fee_mode: u8,      //DEFAULT FOR NEW LOANS, 0 CAPITALIZED INTO DEBT, 1 DEDUCTED FROM DISBURSEMENT
+    pub flash_fee_bps: u16,            //CHARGED ON EVERY FLASH LOAN, ACCRUES TO LPS
+
+    //VERIFICATION
+    pub verification_sla: i64, //SECONDS ADMINS HAVE TO DECIDE, AFTER THAT THE BORROWER CAN RECLAIM THE ASSET
+    pub verification_quorum: u8, //M MATCHING ATTESTATIONS OUT OF THE N ADMINS NEEDED TO APPROVE/REJECT
+    pub attestation_tolerance_bps: u16, //MAX RELATIVE GAP IN WEIGHT AND PURITY FOR TWO APPROVALS TO AGREE
+
+    //BORROW LIMITS
+    pub reserve_ratio_bps: u16,   //SHARE OF POOL ASSETS KEPT IDLE FOR LENDER WITHDRAWALS
+    pub max_utilization_bps: u16, //A BORROW CANNOT PUSH UTILIZATION ABOVE THIS
 
     //POOL STATE
     pub is_locked: bool, //IN CASE OF AN EMERGENCY POOL CAN BE LOCKED BY THE ADMIN...
+    pub flash_loan_active: bool, //SET BETWEEN FLASH_BORROW AND FLASH_REPAY, NEVER OUTLIVES A TRANSACTION
+    pub flash_loan_amount: u64,  //USDC OUT ON THE CURRENT FLASH LOAN
 
     //PROTOCOL TREASURIES
     pub collateral_escrow: Pubkey,
     pub collateral_verification_escrow: Pubkey,
     pub protocol_fee_vault: Pubkey,
+
+    //PROTOCOL FEES, COLLECTED PER SOURCE INTO THE FEE VAULT
+    pub origination_fees_collected: u64,      //ORIGINATION AND REFINANCE FEES PAID BY BORROWERS
+    pub early_withdrawal_fees_collected: u64, //EARLY WITHDRAWAL FEES PAID BY LENDERS
+    pub reserve_fees_collected: u64,          //RESERVE FACTOR CUT OF THE INTEREST PAID
+    pub protocol_fees_withdrawn: u64,         //SWEPT OUT BY THE OWNER
+    pub liquidation_fees_accrued: u64,        //LIQUIDATION PENALTY LEFT AFTER THE LIQUIDATOR REWARD, OWED UNTIL THE SEIZED ASSET IS SOLD
+    pub liquidation_fees_collected: u64,      //ACCRUED LIQUIDATION SHARE SETTLED INTO THE FEE VAULT FROM SALE PROCEEDS
+}
+
+impl LendingPool {
+    //e Fees collected from every source that are still sitting in the fee vault
+    pub fn protocol_fees_available(&self) -> u64 {
+        (self.origination_fees_collected
+            + self.early_withdrawal_fees_collected
+            + self.liquidation_fees_collected
+            + self.reserve_fees_collected)
+            .saturating_sub(self.protocol_fees_withdrawn)
+    }
+
+    //e Part of an interest amount that belongs to protocol reserves
+    pub fn reserve_share(&self, interest: u64) -> Result<u64> {
+        to_u64(mul_div(interest as u128, self.reserve_factor_bps as u128, BPS)?)
+    }
+
+    //e idle cash + outstanding principal + LP share of accrued interest - bad debt
+    pub fn total_pool_assets(&self) -> Result<u64> {
+        let lp_interest = self
+            .total_interest_accrued
+            .saturating_sub(self.total_reserves_accrued);
+        let gross = (self.total_idle_usdc as u128)
+            .checked_add(self.total_borrowed as u128)
+            .ok_or(error!(Errors::MathOverflow))?
+            .checked_add(lp_interest as u128)
+            .ok_or(error!(Errors::MathOverflow))?;
+
+        to_u64(gross.saturating_sub(self.total_bad_debt as u128))
+    }
+
+    //e USDC per LP share, scaled by WAD. 1:1 until the first share is minted
+    pub fn exchange_rate(&self) -> Result<u128> {
+        if self.lp_total_supply == 0 {
+            return Ok(WAD);
+        }
+        mul_div(
+            self.total_pool_assets()? as u128,
+            WAD,
+            self.lp_total_supply as u128,
+        )
+    }
+
+    //e Shares minted for a deposit, rounded down in favour of the pool
+    pub fn shares_for_amount(&self, amount: u64) -> Result<u64> {
+        let exchange_rate = self.exchange_rate()?;
+        require!(exchange_rate > 0, Errors::PoolInsolvent);
+        to_u64(mul_div(amount as u128, WAD, exchange_rate)?)
+    }
+
+    //e USDC owed for burning shares, rounded down in favour of the pool
+    pub fn amount_for_shares(&self, shares: u64) -> Result<u64> {
+        to_u64(mul_div(shares as u128, self.exchange_rate()?, WAD)?)
+    }
+
+    //e Idle cash that isn't already promised to the withdrawal queue
+    pub fn available_liquidity(&self) -> Result<u64> {
+        let queued_amount = self.amount_for_shares(self.total_queued_shares)?;
+        Ok(self.total_idle_usdc.saturating_sub(queued_amount))
+    }
+
+    //e Moves both indexes to now and books the interest every open loan earned since the last checkpoint.
+    //e Variable loans pay the rate the pool was at over the elapsed period
+    pub fn accrue_interest(&mut self, rate_model: &RateModel, current_time: i64) -> Result<()> {
+        if self.last_accrual_ts == 0 || current_time <= self.last_accrual_ts {
+            self.last_accrual_ts = self.last_accrual_ts.max(current_time);
+            return Ok(());
+        }
+
+        let time_delta = (current_time - self.last_accrual_ts) as u128;
+        let index_delta = mul_div(time_delta, WAD, SECONDS_PER_YEAR as u128)?;
+        let variable_index_delta = mul_div(
+            index_delta,
+            self.current_borrow_rate_bps(rate_model)? as u128,
+            BPS,
+        )?;
+
+        let fixed_interest = mul_div(self.total_weighted_debt, index_delta, BPS * WAD)?;
+        let variable_interest = mul_div(
+            self.total_variable_debt as u128,
+            variable_index_delta,
+            WAD,
+        )?;
+        let interest = to_u64(fixed_interest + variable_interest)?;
+
+        //e The reserve cut is taken now at the current factor, a later factor change doesn't touch it
+        let reserve_factor = self.reserve_factor_bps as u128;
+        self.reserve_index = self
+            .reserve_index
+            .checked_add(index_delta * reserve_factor)
+            .ok_or(error!(Errors::MathOverflow))?;
+        self.reserve_variable_index = self
+            .reserve_variable_index
+            .checked_add(variable_index_delta * reserve_factor)
+            .ok_or(error!(Errors::MathOverflow))?;
+        self.total_reserves_accrued = self
+            .total_reserves_accrued
+            .checked_add(self.reserve_share(interest)?)
+            .ok_or(error!(Errors::MathOverflow))?;
+
+        self.borrow_index = self
+            .borrow_index
+            .checked_add(index_delta)
+            .ok_or(error!(Errors::MathOverflow))?;
+        self.variable_rate_index = self
+            .variable_rate_index
+            .checked_add(variable_index_delta)
+            .ok_or(error!(Errors::MathOverflow))?;
+        self.total_interest_accrued = self
+            .total_interest_accrued
+            .checked_add(interest)
+            .ok_or(error!(Errors::MathOverflow))?;
+        self.last_accrual_ts = current_time;
+
+        Ok(())
+    }
+
+    //e Accrues the pool, then brings the loan up to the same indexes and reprices it if it's variable.
+    //e The pool sum is linear between checkpoints, the gap to the exact (compounded) loan interest is booked here
+    pub fn settle_loan(
+        &mut self,
+        rate_model: &RateModel,
+        loan: &mut LoanState,
+        current_time: i64,
+    ) -> Result<u64> {
+        self.accrue_interest(rate_model, current_time)?;
+
+        let estimated = to_u64(mul_div(
+            loan.interest_base(self.interest_mode) as u128,
+            loan.rate_years(self.borrow_index, self.variable_rate_index)?,
+            WAD,
+        )?)?;
+
+        //e Average reserve factor over the time since the loan's snapshots, read before they move
+        let reserve_rate = self.loan_reserve_rate(loan)?;
+
+        self.remove_loan_weight(loan);
+        let last_accrued = loan.last_interest_accrued;
+        let interest = loan.accrue_interest(
+            self.borrow_index,
+            self.variable_rate_index,
+            self.interest_mode,
+            self.compounding_period,
+            current_time,
+        )?;
+
+        //e Past maturity the penalty APR runs on top, it isn't in the pool estimate so it's booked in full
+        let penalty_interest =
+            loan.accrue_penalty_interest(self.penalty_apr_bps, last_accrued, current_time)?;
+        self.total_interest_accrued = self
+            .total_interest_accrued
+            .checked_add(penalty_interest)
+            .ok_or(error!(Errors::MathOverflow))?;
+
+        if loan.rate_type == RATE_TYPE_VARIABLE {
+            loan.borrow_apr_bps = self.current_borrow_rate_bps(rate_model)?;
+        }
+        self.add_loan_weight(loan)?;
+
+        if interest >= estimated {
+            self.total_interest_accrued = self
+                .total_interest_accrued
+                .checked_add(interest - estimated)
+                .ok_or(error!(Errors::MathOverflow))?;
+        } else {
+            self.release_interest(estimated - interest);
+        }
+
+        //e The pool took the reserve on the estimate as it accrued, the gap is trued up like the interest
+        let loan_reserve = to_u64(mul_div(
+            (interest + penalty_interest) as u128,
+            reserve_rate,
+            WAD,
+        )?)?;
+        let estimated_reserve = to_u64(mul_div(estimated as u128, reserve_rate, WAD)?)?;
+        loan.reserve_accrued = loan
+            .reserve_accrued
+            .checked_add(loan_reserve)
+            .ok_or(error!(Errors::MathOverflow))?;
+        loan.reserve_index_snapshot = self.reserve_index;
+        loan.reserve_variable_index_snapshot = self.reserve_variable_index;
+        if loan_reserve >= estimated_reserve {
+            self.total_reserves_accrued = self
+                .total_reserves_accrued
+                .checked_add(loan_reserve - estimated_reserve)
+                .ok_or(error!(Errors::MathOverflow))?;
+        } else {
+            self.release_reserves(estimated_reserve - loan_reserve);
+        }
+
+        Ok(interest + penalty_interest)
+    }
+
+    //e A loan's debt or rate is about to change, take its weight out of the pool sums
+    pub fn remove_loan_weight(&mut self, loan: &LoanState) {
+        if loan.rate_type == RATE_TYPE_VARIABLE {
+            self.total_variable_debt = self
+                .total_variable_debt
+                .saturating_sub(loan.interest_base(self.interest_mode));
+        } else {
+            self.total_weighted_debt = self
+                .total_weighted_debt
+                .saturating_sub(loan.weighted_debt(self.interest_mode));
+        }
+    }
+
+    pub fn add_loan_weight(&mut self, loan: &LoanState) -> Result<()> {
+        if loan.rate_type == RATE_TYPE_VARIABLE {
+            self.total_variable_debt = self
+                .total_variable_debt
+                .checked_add(loan.interest_base(self.interest_mode))
+                .ok_or(error!(Errors::MathOverflow))?;
+        } else {
+            self.total_weighted_debt = self
+                .total_weighted_debt
+                .checked_add(loan.weighted_debt(self.interest_mode))
+                .ok_or(error!(Errors::MathOverflow))?;
+        }
+        Ok(())
+    }
+
+    pub fn current_borrow_rate_bps(&self, rate_model: &RateModel) -> Result<u16> {
+        rate_model.borrow_rate_bps(self.utilization_rate_bps()?)
+    }
+
+    //e What lenders earn: the yearly interest of the whole loan book spread over pool assets, compounded continuously
+    pub fn supply_apy_bps(&self, rate_model: &RateModel) -> Result<u64> {
+        let total_assets = self.total_pool_assets()?;
+        if total_assets == 0 {
+            return Ok(0);
+        }
+
+        let variable_rate = self.current_borrow_rate_bps(rate_model)? as u128;
+        let yearly_interest = self.total_weighted_debt / BPS
+            + mul_div(self.total_variable_debt as u128, variable_rate, BPS)?;
+        //e Lenders only earn what's left after the reserve factor
+        let yearly_interest = mul_div(
+            yearly_interest,
+            BPS - self.reserve_factor_bps as u128,
+            BPS,
+        )?;
+
+        let supply_apr = mul_div(yearly_interest, WAD, total_assets as u128)?;
+        to_u64(mul_div(exp_minus_one_wad(supply_apr)?, BPS, WAD)?)
+    }
+
+    //e Interest left the loan book, either paid in USDC or written off
+    pub fn release_interest(&mut self, interest: u64) {
+        self.total_interest_accrued = self.total_interest_accrued.saturating_sub(interest);
+    }
+
+    //e Reserve cut left the loan book together with its interest
+    pub fn release_reserves(&mut self, reserves: u64) {
+        self.total_reserves_accrued = self.total_reserves_accrued.saturating_sub(reserves);
+    }
+
+    //e Reserve factor averaged over the interest the loan accrued since its snapshots, scaled by WAD.
+    //e Falls back to the current factor when its index didn't move
+    pub fn loan_reserve_rate(&self, loan: &LoanState) -> Result<u128> {
+        let (index_delta, reserve_delta) = if loan.rate_type == RATE_TYPE_VARIABLE {
+            (
+                self.variable_rate_index
+                    .saturating_sub(loan.variable_index_snapshot),
+                self.reserve_variable_index
+                    .saturating_sub(loan.reserve_variable_index_snapshot),
+            )
+        } else {
+            (
+                self.borrow_index.saturating_sub(loan.borrow_index_snapshot),
+                self.reserve_index.saturating_sub(loan.reserve_index_snapshot),
+            )
+        };
+        if index_delta == 0 {
+            return mul_div(self.reserve_factor_bps as u128, WAD, BPS);
+        }
+        mul_div(reserve_delta, WAD, index_delta * BPS)
+    }
+
+    //e Available liquidity minus the reserve held back for withdrawals
+    pub fn borrowable_liquidity(&self) -> Result<u64> {
+        let reserve = to_u64(mul_div(
+            self.total_pool_assets()? as u128,
+            self.reserve_ratio_bps as u128,
+            BPS,
+        )?)?;
+        Ok(self.available_liquidity()?.saturating_sub(reserve))
+    }
+
+    //e Utilization once `amount` more is lent out, pool assets don't change as idle cash becomes principal
+    pub fn utilization_after_borrow_bps(&self, amount: u64) -> Result<u64> {
+        let total_assets = self.total_pool_assets()?;
+        if total_assets == 0 {
+            return Ok(0);
+        }
+        let outstanding = total_assets.saturating_sub(self.total_idle_usdc) as u128 + amount as u128;
+        to_u64(mul_div(outstanding, BPS, total_assets as u128)?)
+    }
+
+    //e (Outstanding principal + accrued interest - bad debt) * 10_000 / pool assets
+    pub fn utilization_rate_bps(&self) -> Result<u64> {
+        let total_assets = self.total_pool_assets()?;
+        if total_assets == 0 {
+            return Ok(0);
+        }
+        let outstanding = total_assets.saturating_sub(self.total_idle_usdc);
+        to_u64(mul_div(outstanding as u128, BPS, total_assets as u128)?)
+    }
+}
+
+//e One point of a rate curve
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct RatePoint {
+    pub utilization_bps: u16,
+    pub apr_bps: u16,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RateModel {
+    pub lending_pool: Pubkey,
+    pub model_type: u8, //0 STEP TIERS, 1 LINEAR WITH KINK, 2 PIECEWISE LINEAR
+
+    //LINEAR WITH KINK
+    pub base_rate_bps: u16,           //APR AT 0% UTILIZATION
+    pub slope_1_bps: u16,             //APR ADDED FROM 0% UP TO THE KINK
+    pub slope_2_bps: u16,             //APR ADDED FROM THE KINK UP TO 100%
+    pub optimal_utilization_bps: u16, //THE KINK
+
+    //STEP TIERS / PIECEWISE LINEAR, SORTED BY UTILIZATION
+    pub point_count: u8,
+    pub points: [RatePoint; MAX_RATE_POINTS],
+
+    pub bump: u8,
+}
+
+impl RateModel {
+    //e Tiers and curve points must be sorted by utilization
+    pub fn validate_points(points: &[RatePoint]) -> Result<()> {
+        require!(
+            !points.is_empty() && points.len() <= MAX_RATE_POINTS,
+            Errors::InvalidRateModel
+        );
+        require!(
+            points
+                .windows(2)
+                .all(|w| w[0].utilization_bps < w[1].utilization_bps),
+            Errors::InvalidRateModel
+        );
+        Ok(())
+    }
+
+    pub fn borrow_rate_bps(&self, utilization_bps: u64) -> Result<u16> {
+        let utilization_bps = utilization_bps.min(BPS as u64);
+
+        let rate: u64 = match self.model_type {
+            RATE_MODEL_KINKED => {
+                let base = self.base_rate_bps as u64;
+                let kink = self.optimal_utilization_bps as u64;
+                if utilization_bps <= kink {
+                    base + self.slope_1_bps as u64 * utilization_bps / kink
+                } else {
+                    base + self.slope_1_bps as u64
+                        + (self.slope_2_bps as u64 * (utilization_bps - kink))
+                            / (BPS as u64 - kink).max(1)
+                }
+            }
+            RATE_MODEL_PIECEWISE_LINEAR => {
+                let points = self.active_points();
+                let first = points[0];
+                let last = points[points.len() - 1];
+
+                if utilization_bps <= first.utilization_bps as u64 {
+                    first.apr_bps as u64
+                } else if utilization_bps >= last.utilization_bps as u64 {
+                    last.apr_bps as u64
+                } else {
+                    //e Interpolate inside the segment utilization falls in
+                    let upper = points
+                        .iter()
+                        .position(|p| p.utilization_bps as u64 >= utilization_bps)
+                        .unwrap_or(points.len() - 1);
+                    let (lo, hi) = (points[upper - 1], points[upper]);
+                    let (u0, u1) = (lo.utilization_bps as i64, hi.utilization_bps as i64);
+                    let (r0, r1) = (lo.apr_bps as i64, hi.apr_bps as i64);
+                    (r0 + (r1 - r0) * (utilization_bps as i64 - u0) / (u1 - u0).max(1)) as u64
+                }
+            }
+            _ => {
+                //e Step function, the rate of the last tier utilization has reached
+                let points = self.active_points();
+                points
+                    .iter()
+                    .rfind(|p| utilization_bps >= p.utilization_bps as u64)
+                    .unwrap_or(&points[0])
+                    .apr_bps as u64
+            }
+        };
+
+        Ok(rate.min(u16::MAX as u64) as u16)
+    }
+
+    pub fn active_points(&self) -> &[RatePoint] {
+        &self.points[..(self.point_count as usize).clamp(1, MAX_RATE_POINTS)]
+    }
+}
+
+//e Rates returned by the rate getter
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RateQuote {
+    pub utilization_bps: u64,
+    pub borrow_apr_bps: u16,
+    pub supply_apy_bps: u64,
+}
+
+//e Protocol fees and reserves collected so far, per source
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ProtocolFees {
+    pub origination_fees: u64,
+    pub early_withdrawal_fees: u64,
+    pub liquidation_fees_accrued: u64, //NOT WITHDRAWABLE UNTIL SETTLED, NOT PART OF AVAILABLE
+    pub liquidation_fees: u64,
+    pub reserves_accrued: u64, //BOOKED ON INTEREST NOT PAID YET
+    pub reserves: u64,
+    pub withdrawn: u64,
+    pub available: u64,
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct LoanState {
+    pub lending_pool: Pubkey,
     pub borrower: Pubkey,
+    pub loan_id: u64, //INDEX OF THIS LOAN IN THE BORROWER PROFILE
     pub nft_mint: Pubkey,
     pub verification_id: u32,
-    pub is_rejected: bool,
-    pub is_sent_for_verification: bool,
-    pub is_verified: bool,
     pub principal_borrowed: u64,
     pub interest_accrued: u64,
     pub outstanding_debt: u64,
@@ -85,17 +574,684 @@ pub struct LoanState {
     pub borrow_apr_bps: u16,
     pub current_owner_asset: Pubkey,
     pub current_health_factor: u64, 
+    pub borrow_index_snapshot: u128, //POOL BORROW INDEX THE INTEREST WAS LAST ACCRUED AT
+    pub pending_interest: u64, //INTEREST OF THE CURRENT COMPOUNDING PERIOD, NOT YET CAPITALIZED
+    pub rate_type: u8, //0 FIXED AT ORIGINATION, 1 VARIABLE (REPRICED ON EVERY ACCRUAL)
+    pub variable_index_snapshot: u128, //POOL VARIABLE RATE INDEX THE INTEREST WAS LAST ACCRUED AT
+    pub reserve_index_snapshot: u128, //POOL RESERVE INDEXES THE INTEREST WAS LAST ACCRUED AT
+    pub reserve_variable_index_snapshot: u128,
+    pub reserve_accrued: u64, //PART OF INTEREST_ACCRUED THAT BELONGS TO PROTOCOL RESERVES
+    pub term_seconds: i64, //LOAN TERM CHOSEN AT BORROW
+    pub maturity_ts: i64,  //BORROWED_AT + TERM, DEBT IS DUE BY THEN
+    pub origination_fee_mode: u8, //0 CAPITALIZED INTO DEBT, 1 DEDUCTED FROM EVERY DRAWDOWN
+    pub reserve_owed: u64, //RESERVE CUT OF INTEREST CAPITALIZED ON REFINANCE, OWED TO THE PROTOCOL
+
+    //INSTALLMENT SCHEDULE, FIXED AT ORIGINATION
+    pub repayment_type: u8,         //0 BULLET, 1 INSTALLMENTS
+    pub installment_count: u16,     //ONE EVERY INSTALLMENT_INTERVAL OVER THE TERM
+    pub installment_amount: u64,    //PRINCIPAL + INTEREST PER INSTALLMENT
+    pub installments_paid: u16,     //INSTALLMENTS COVERED BY SCHEDULE_REPAID
+    pub schedule_repaid: u64,       //PAID TOWARDS THE SCHEDULE (EVERYTHING BUT LATE FEES), PREPAYMENTS INCLUDED
+    pub missed_installments: u16,   //INSTALLMENTS PAST THEIR DUE DATE AND NOT PAID
+    pub late_fees_charged_upto: u16, //INSTALLMENTS ALREADY CHARGED A LATE FEE
+    pub late_fees: u64,             //UNPAID LATE FEES
+}
+
+impl LoanState {
+    //e Interest since the loan's index snapshot under the pool's interest mode, booked into the loan and the snapshot moved forward
+    pub fn accrue_interest(
+        &mut self,
+        borrow_index: u128,
+        variable_rate_index: u128,
+        interest_mode: u8,
+        compounding_period: i64,
+        current_time: i64,
+    ) -> Result<u64> {
+        let rate_years = self.rate_years(borrow_index, variable_rate_index)?;
+        let annual_rate = mul_div(self.borrow_apr_bps as u128, WAD, BPS)?;
+
+        let interest = match interest_mode {
+            INTEREST_MODE_COMPOUND_PER_SECOND => {
+                //e debt * (e^(apr * years) - 1), accrued interest compounds along with the principal
+                let growth = exp_minus_one_wad(rate_years)?;
+                self.pending_interest = 0;
+                to_u64(mul_div(
+                    self.interest_base(interest_mode) as u128,
+                    growth,
+                    WAD,
+                )?)?
+            }
+            INTEREST_MODE_COMPOUND_PERIODIC => {
+                self.accrue_periodic_interest(annual_rate, compounding_period, current_time)?
+            }
+            _ => {
+                self.pending_interest = 0;
+                to_u64(mul_div(
+                    self.interest_base(interest_mode) as u128,
+                    rate_years,
+                    WAD,
+                )?)?
+            }
+        };
+
+        self.interest_accrued = self
+            .interest_accrued
+            .checked_add(interest)
+            .ok_or(error!(Errors::MathOverflow))?;
+        self.borrow_index_snapshot = borrow_index;
+        self.variable_index_snapshot = variable_rate_index;
+        self.last_interest_accrued = current_time;
+
+        Ok(interest)
+    }
+
+    //e Periods run from borrowed_at. Interest accrues simply inside a period and is capitalized at each period end
+    fn accrue_periodic_interest(
+        &mut self,
+        annual_rate: u128,
+        compounding_period: i64,
+        current_time: i64,
+    ) -> Result<u64> {
+        let last_accrued = self.last_interest_accrued;
+        if current_time <= last_accrued || compounding_period <= 0 {
+            return Ok(0);
+        }
+
+        let simple_interest = |base: u128, seconds: i64| -> Result<u128> {
+            mul_div(
+                base,
+                annual_rate * seconds as u128,
+                WAD * SECONDS_PER_YEAR as u128,
+            )
+        };
+
+        let capitalized_before =
+            self.interest_base(INTEREST_MODE_COMPOUND_PERIODIC) as u128;
+        let pending_before = self.pending_interest as u128;
+
+        let mut base = capitalized_before;
+        let mut pending = pending_before;
+
+        let periods_before = (last_accrued - self.borrowed_at) / compounding_period;
+        let periods_now = (current_time - self.borrowed_at) / compounding_period;
+
+        if periods_now == periods_before {
+            pending += simple_interest(base, current_time - last_accrued)?;
+        } else {
+            //e Finish the period we were in and capitalize it
+            let first_period_end = self.borrowed_at + (periods_before + 1) * compounding_period;
+            pending += simple_interest(base, first_period_end - last_accrued)?;
+            base += pending;
+
+            //e Whole periods compound at apr * period / year each
+            let full_periods = (periods_now - periods_before - 1) as u64;
+            let period_rate = mul_div(
+                annual_rate,
+                compounding_period as u128,
+                SECONDS_PER_YEAR as u128,
+            )?;
+            base = mul_div(base, pow_wad(WAD + period_rate, full_periods)?, WAD)?;
+
+            //e Start of the current period, not capitalized yet
+            let current_period_start = self.borrowed_at + periods_now * compounding_period;
+            pending = simple_interest(base, current_time - current_period_start)?;
+        }
+
+        self.pending_interest = to_u64(pending)?;
+        to_u64((base + pending) - (capitalized_before + pending_before))
+    }
+
+    //e Simple penalty APR on the principal for the part of (last_accrued, current_time] that is past maturity
+    pub fn accrue_penalty_interest(
+        &mut self,
+        penalty_apr_bps: u16,
+        last_accrued: i64,
+        current_time: i64,
+    ) -> Result<u64> {
+        if self.maturity_ts == 0 || current_time <= self.maturity_ts {
+            return Ok(0);
+        }
+
+        let overdue_seconds = current_time - last_accrued.max(self.maturity_ts);
+        if overdue_seconds <= 0 {
+            return Ok(0);
+        }
+
+        let penalty_interest = to_u64(mul_div(
+            self.principal_borrowed as u128 * penalty_apr_bps as u128,
+            overdue_seconds as u128,
+            BPS * SECONDS_PER_YEAR as u128,
+        )?)?;
+
+        self.interest_accrued = self
+            .interest_accrued
+            .checked_add(penalty_interest)
+            .ok_or(error!(Errors::MathOverflow))?;
+
+        Ok(penalty_interest)
+    }
+
+    pub fn is_matured(&self, current_time: i64) -> bool {
+        self.maturity_ts > 0 && current_time >= self.maturity_ts
+    }
+
+    //e APR * years since the snapshot, scaled by WAD. Variable loans read it straight off the pool's variable index
+    pub fn rate_years(&self, borrow_index: u128, variable_rate_index: u128) -> Result<u128> {
+        if self.rate_type == RATE_TYPE_VARIABLE {
+            return Ok(variable_rate_index.saturating_sub(self.variable_index_snapshot));
+        }
+        mul_div(
+            borrow_index.saturating_sub(self.borrow_index_snapshot),
+            self.borrow_apr_bps as u128,
+            BPS,
+        )
+    }
+
+    //e The part of the debt interest is charged on
+    pub fn interest_base(&self, interest_mode: u8) -> u64 {
+        match interest_mode {
+            INTEREST_MODE_COMPOUND_PER_SECOND => self.principal_borrowed + self.interest_accrued,
+            INTEREST_MODE_COMPOUND_PERIODIC => {
+                self.principal_borrowed + self.interest_accrued - self.pending_interest
+            }
+            _ => self.principal_borrowed,
+        }
+    }
+
+    pub fn weighted_debt(&self, interest_mode: u8) -> u128 {
+        self.interest_base(interest_mode) as u128 * self.borrow_apr_bps as u128
+    }
+
+    //e Principal + booked interest + origination fee, owed reserve and late fees, penalty not included
+    pub fn base_debt(&self) -> u64 {
+        self.principal_borrowed
+            + self.interest_accrued
+            + self.origination_fee
+            + self.reserve_owed
+            + self.late_fees
+    }
+
+    //e Level payment that amortizes the debt over the term: debt * r / (1 - (1 + r)^-n), r the rate per interval
+    pub fn set_installment_schedule(&mut self) -> Result<()> {
+        let installment_count = self.term_seconds / INSTALLMENT_INTERVAL;
+        require!(
+            installment_count > 0 && installment_count <= u16::MAX as i64,
+            Errors::InvalidLoanTerm
+        );
+
+        let debt = self.base_debt() as u128;
+        let period_rate = mul_div(
+            self.borrow_apr_bps as u128 * INSTALLMENT_INTERVAL as u128,
+            WAD,
+            BPS * SECONDS_PER_YEAR as u128,
+        )?;
+
+        let installment_amount = if period_rate == 0 {
+            debt.div_ceil(installment_count as u128)
+        } else {
+            let growth = pow_wad(WAD + period_rate, installment_count as u64)?;
+            mul_div(mul_div(debt, period_rate, WAD)?, growth, growth - WAD)?
+        };
+
+        self.repayment_type = REPAYMENT_TYPE_INSTALLMENT;
+        self.installment_count = installment_count as u16;
+        self.installment_amount = to_u64(installment_amount)?;
+        Ok(())
+    }
+
+    //e Counts installments past due and charges the late fee once on each newly missed one
+    pub fn update_missed_installments(&mut self, late_fee_bps: u16, current_time: i64) -> Result<()> {
+        if self.repayment_type != REPAYMENT_TYPE_INSTALLMENT {
+            return Ok(());
+        }
+
+        let elapsed_intervals = (current_time - self.borrowed_at).max(0) / INSTALLMENT_INTERVAL;
+        let installments_due = elapsed_intervals.min(self.installment_count as i64) as u16;
+        self.missed_installments = installments_due.saturating_sub(self.installments_paid);
+
+        let already_charged = self.late_fees_charged_upto.max(self.installments_paid);
+        let newly_missed = installments_due.saturating_sub(already_charged);
+        if newly_missed > 0 {
+            let late_fee = to_u64(mul_div(
+                self.installment_amount as u128 * newly_missed as u128,
+                late_fee_bps as u128,
+                BPS,
+            )?)?;
+            self.late_fees = self
+                .late_fees
+                .checked_add(late_fee)
+                .ok_or(error!(Errors::MathOverflow))?;
+            self.late_fees_charged_upto = installments_due;
+        }
+        Ok(())
+    }
+
+    //e Any repayment counts towards the schedule, so prepaying through repay also covers upcoming installments
+    pub fn credit_installments(&mut self, allocation: &RepaymentAllocation) {
+        if self.repayment_type != REPAYMENT_TYPE_INSTALLMENT || self.installment_amount == 0 {
+            return;
+        }
+        let late_fees = allocation.fees - allocation.origination_fee - allocation.reserve;
+        self.schedule_repaid += allocation.total() - late_fees;
+
+        let covered = self.schedule_repaid / self.installment_amount;
+        self.installments_paid = if self.base_debt() == 0 {
+            self.installment_count
+        } else {
+            covered.min(self.installment_count as u64) as u16
+        };
+    }
+
+    //e What the next pay_installment call takes, the last installment clears whatever is left
+    pub fn next_installment_due(&self) -> u64 {
+        let debt = self.base_debt();
+        if self.installments_paid + 1 >= self.installment_count {
+            return debt;
+        }
+        (self.installment_amount + self.late_fees).min(debt)
+    }
+
+    //e Pays down interest first, then fees, then principal. Anything above the debt is left unapplied
+    pub fn apply_repayment(&mut self, amount: u64) -> Result<RepaymentAllocation> {
+        let interest = amount.min(self.interest_accrued);
+        //e The interest paid carries its booked share of the reserves, all of it once the interest is cleared
+        let interest_reserve = if interest == self.interest_accrued {
+            self.reserve_accrued
+        } else {
+            to_u64(mul_div(
+                interest as u128,
+                self.reserve_accrued as u128,
+                self.interest_accrued as u128,
+            )?)?
+        };
+        self.reserve_accrued -= interest_reserve;
+        self.interest_accrued -= interest;
+        //e Uncapitalized interest is paid off first
+        self.pending_interest -= interest.min(self.pending_interest);
+
+        let origination_fee = (amount - interest).min(self.origination_fee);
+        self.origination_fee -= origination_fee;
+        let reserve = (amount - interest - origination_fee).min(self.reserve_owed);
+        self.reserve_owed -= reserve;
+        let late_fees = (amount - interest - origination_fee - reserve).min(self.late_fees);
+        self.late_fees -= late_fees;
+        let fees = origination_fee + reserve + late_fees;
+
+        let principal = (amount - interest - fees).min(self.principal_borrowed);
+        self.principal_borrowed -= principal;
+
+        self.outstanding_debt = self.base_debt();
+
+        Ok(RepaymentAllocation {
+            interest,
+            interest_reserve,
+            fees,
+            origination_fee,
+            reserve,
+            principal,
+        })
+    }
+}
+
+//e How a repayment was split across the loan balances
+pub struct RepaymentAllocation {
+    pub interest: u64,
+    pub interest_reserve: u64, //PART OF THE INTEREST THAT BELONGS TO PROTOCOL RESERVES
+    pub fees: u64,
+    pub origination_fee: u64, //PART OF THE FEES THAT BELONGS TO THE PROTOCOL
+    pub reserve: u64,         //OWED RESERVE PAID OFF, ALSO PART OF THE FEES
+    pub principal: u64,
+}
+
+impl RepaymentAllocation {
+    pub fn total(&self) -> u64 {
+        self.interest + self.fees + self.principal
+    }
 }
 
 #[account]
 #[derive(InitSpace)]
+pub struct BorrowerProfile {
+    pub lending_pool: Pubkey,
+    pub borrower: Pubkey,
+    pub loan_count: u64, //LOANS EVER OPENED, NEXT LOAN ID
+    pub open_loans: u64, //LOANS NOT YET REPAID/LIQUIDATED/RETURNED
+    pub bump: u8,
+}
+
+#[account]
 pub struct Lender {
+    pub lending_pool: Pubkey,
     pub owner: Pubkey,
     pub lp_shares: u64,
-    pub deposited_at: i64,
-    pub total_deposited: u64,
+    pub total_deposited: u64, //COST BASIS OF THE SHARES STILL HELD
     pub total_interest_accrued: u64,
     pub bump: u8,
+    pub lots: Vec<DepositLot>, //ONE LOT PER DEPOSIT, OLDEST FIRST
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct DepositLot {
+    pub amount: u64, //USDC COST BASIS LEFT IN THE LOT
+    pub shares: u64, //LP SHARES LEFT IN THE LOT
+    pub deposited_at: i64,
+}
+
+//e What consuming shares out of the lots resolved to
+pub struct ConsumedLots {
+    pub cost_basis: u64,
+    pub early_shares: u64, //SHARES TAKEN FROM LOTS STILL INSIDE THEIR WITHDRAWAL EPOCH
+}
+
+impl Lender {
+    pub const MAX_LOTS: usize = 32;
+
+    pub fn space(lot_count: usize) -> usize {
+        8 + 32 + 32 + 8 + 8 + 8 + 1 + 4 + (lot_count * DepositLot::INIT_SPACE) //DISCRIMINATOR + FIELDS + VEC LENGTH PREFIX + LOTS
+    }
+
+    pub fn add_lot(&mut self, amount: u64, shares: u64, deposited_at: i64) -> Result<()> {
+        require!(self.lots.len() < Self::MAX_LOTS, Errors::MaxDepositLots);
+        self.lots.push(DepositLot {
+            amount,
+            shares,
+            deposited_at,
+        });
+        self.lp_shares += shares;
+        self.total_deposited += amount;
+        Ok(())
+    }
+
+    //e Shares at the front of the queue whose lots are past the withdrawal epoch
+    pub fn matured_shares(&self, current_time: i64, withdrawal_epoch: i64) -> u64 {
+        self.lots
+            .iter()
+            .take_while(|lot| current_time - lot.deposited_at >= withdrawal_epoch)
+            .map(|lot| lot.shares)
+            .sum()
+    }
+
+    //e Takes shares out of the lots FIFO, emptied lots are dropped
+    pub fn consume_shares(
+        &mut self,
+        shares: u64,
+        current_time: i64,
+        withdrawal_epoch: i64,
+    ) -> Result<ConsumedLots> {
+        require!(shares <= self.lp_shares, Errors::InsufficientShares);
+
+        let mut remaining = shares;
+        let mut consumed = ConsumedLots {
+            cost_basis: 0,
+            early_shares: 0,
+        };
+
+        while remaining > 0 {
+            let lot = self.lots.first_mut().ok_or(error!(Errors::InsufficientShares))?;
+            let taken = remaining.min(lot.shares);
+            let cost_basis = to_u64(mul_div(lot.amount as u128, taken as u128, lot.shares as u128)?)?;
+
+            if current_time - lot.deposited_at < withdrawal_epoch {
+                consumed.early_shares += taken;
+            }
+            consumed.cost_basis += cost_basis;
+
+            lot.shares -= taken;
+            lot.amount -= cost_basis;
+            remaining -= taken;
+
+            if lot.shares == 0 {
+                self.lots.remove(0);
+            }
+        }
+
+        self.lp_shares -= shares;
+        self.total_deposited -= consumed.cost_basis;
+        Ok(consumed)
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalRequest {
+    pub lending_pool: Pubkey,
+    pub owner: Pubkey,
+    pub request_id: u64,
+    pub lp_shares: u64,    //LP SHARES ESCROWED IN THE POOL LP VAULT
+    pub cost_basis: u64,   //USDC COST BASIS OF THE ESCROWED SHARES
+    pub requested_at: i64,
+    pub claimable_at: i64, //REQUESTED_AT + WITHDRAWAL EPOCH
+    pub bump: u8,
+}
+
+//e Where a pledged asset is in the verification process
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum VerificationStatus {
+    Pending,     //ASSET IN THE VERIFICATION VAULT, NO ADMIN HAS PICKED IT UP
+    UnderReview, //AN ADMIN IS ASSAYING IT
+    Approved,    //CAN BE DEPOSITED AS COLLATERAL
+    Rejected,    //WAITING FOR THE BORROWER TO COLLECT IT
+    Cancelled,   //WITHDRAWN BY THE BORROWER BEFORE REVIEW
+    Returned,    //ASSET BACK WITH THE BORROWER
+}
+
+//e Assay report an attestation is based on, the document itself lives off-chain
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct AssayReport {
+    pub report_hash: [u8; 32], //SHA-256 OF THE REPORT DOCUMENT
+    #[max_len(MAX_REPORT_URI_LEN)]
+    pub report_uri: String,
+    pub assayer: Pubkey, //ASSAYER/CUSTODIAN THAT ISSUED THE REPORT
+    #[max_len(MAX_BAR_SERIAL_LEN)]
+    pub bar_serial: String,
+    #[max_len(MAX_REFINERY_LEN)]
+    pub refinery: String,
+    pub assayed_at: i64, //DATE ON THE REPORT
+}
+
+impl AssayReport {
+    pub fn validate(&self, current_time: i64) -> Result<()> {
+        require!(
+            self.report_uri.len() <= MAX_REPORT_URI_LEN
+                && self.bar_serial.len() <= MAX_BAR_SERIAL_LEN
+                && self.refinery.len() <= MAX_REFINERY_LEN,
+            Errors::InvalidAssayReport
+        );
+        require!(
+            self.report_hash != [0u8; 32] && !self.bar_serial.is_empty(),
+            Errors::InvalidAssayReport
+        );
+        require!(
+            self.assayed_at > 0 && self.assayed_at <= current_time,
+            Errors::InvalidAssayReport
+        );
+        Ok(())
+    }
+}
+
+//e One admin's assay of a verification request
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct Attestation {
+    pub verifier: Pubkey,
+    pub approve: bool,
+    pub weight_in_grams: i64,
+    pub purity_in_bps: u16,
+    pub attested_at: i64,
+    pub conflicting: bool, //DISAGREED WITH AN EARLIER ATTESTATION
+    pub report: AssayReport,
+}
+
+impl Attestation {
+    //e Same decision, and for approvals the same bar with weight and purity within tolerance of each other
+    pub fn agrees_with(&self, other: &Attestation, tolerance_bps: u16) -> bool {
+        if self.approve != other.approve {
+            return false;
+        }
+        !self.approve
+            || (self.report.bar_serial == other.report.bar_serial
+                && within_tolerance(
+                self.weight_in_grams as u128,
+                other.weight_in_grams as u128,
+                tolerance_bps,
+            ) && within_tolerance(
+                self.purity_in_bps as u128,
+                other.purity_in_bps as u128,
+                tolerance_bps,
+            ))
+    }
+}
+
+//e |a - b| <= tolerance of the larger value
+fn within_tolerance(a: u128, b: u128, tolerance_bps: u16) -> bool {
+    a.abs_diff(b) * BPS <= a.max(b) * tolerance_bps as u128
+}
+
+//e What the attestations decided once the quorum is reached
+pub enum QuorumOutcome {
+    Approved {
+        weight_in_grams: i64,
+        purity_in_bps: u16,
+    },
+    Rejected,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VerificationRequest {
+    pub lending_pool: Pubkey,
+    pub loan: Pubkey, //LOAN STATE THE ASSET IS PLEDGED TO
+    pub borrower: Pubkey,
+    pub rwa_asset: Pubkey,
+    pub verification_id: u32,
+    pub status: VerificationStatus,
+    pub reviewer: Pubkey, //ADMIN WHO APPROVED/REJECTED IT
+    pub weight_in_grams: i64,
+    pub purity_in_bps: u16,
+    pub review_deadline: i64, //REQUESTED_AT + POOL SLA, UNDECIDED AFTER THIS THE BORROWER CAN RECLAIM
+
+    //ATTESTATIONS, ONE PER ADMIN. CONFLICTING ONES ARE FLAGGED AND KEPT FOR REVIEW
+    pub attestation_count: u8,
+    pub conflict_count: u8,
+    pub attestations: [Attestation; MAX_ATTESTATIONS],
+    pub report: AssayReport, //REPORT OF THE ATTESTATION THAT DECIDED THE REQUEST
+
+    //TIMESTAMPS OF EVERY TRANSITION, 0 IF IT NEVER HAPPENED
+    pub requested_at: i64,
+    pub review_started_at: i64,
+    pub approved_at: i64,
+    pub rejected_at: i64,
+    pub cancelled_at: i64,
+    pub returned_at: i64,
+    pub bump: u8,
+}
+
+impl VerificationRequest {
+    pub fn can_transition_to(&self, to: VerificationStatus) -> bool {
+        use VerificationStatus::*;
+        matches!(
+            (self.status, to),
+            (Pending, UnderReview)
+                | (Pending, Cancelled)
+                | (UnderReview, Approved)
+                | (UnderReview, Cancelled)
+                | (UnderReview, Rejected)
+                | (Rejected, Returned)
+                | (Cancelled, Returned)
+        )
+    }
+
+    //e Moves the request to a new status and stamps the time, reverts on any transition not in the table above
+    pub fn transition(&mut self, to: VerificationStatus, current_time: i64) -> Result<()> {
+        require!(
+            self.can_transition_to(to),
+            Errors::InvalidVerificationTransition
+        );
+
+        match to {
+            VerificationStatus::Pending => self.requested_at = current_time,
+            VerificationStatus::UnderReview => self.review_started_at = current_time,
+            VerificationStatus::Approved => self.approved_at = current_time,
+            VerificationStatus::Rejected => self.rejected_at = current_time,
+            VerificationStatus::Cancelled => self.cancelled_at = current_time,
+            VerificationStatus::Returned => self.returned_at = current_time,
+        }
+        self.status = to;
+        Ok(())
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.status == VerificationStatus::Approved
+    }
+
+    pub fn active_attestations(&self) -> &[Attestation] {
+        &self.attestations[..self.attestation_count as usize]
+    }
+
+    //e Stores an admin's attestation, flagging it if it disagrees with any earlier one. Returns whether it conflicted
+    pub fn record_attestation(
+        &mut self,
+        attestation: &Attestation,
+        tolerance_bps: u16,
+    ) -> Result<bool> {
+        require!(
+            (self.attestation_count as usize) < MAX_ATTESTATIONS,
+            Errors::MaxAttestations
+        );
+        require!(
+            self.active_attestations()
+                .iter()
+                .all(|a| a.verifier != attestation.verifier),
+            Errors::AlreadyAttested
+        );
+
+        let conflicting = self
+            .active_attestations()
+            .iter()
+            .any(|a| !a.agrees_with(attestation, tolerance_bps));
+        if conflicting {
+            self.conflict_count += 1;
+        }
+
+        self.attestations[self.attestation_count as usize] = Attestation {
+            conflicting,
+            ..attestation.clone()
+        };
+        self.attestation_count += 1;
+        Ok(conflicting)
+    }
+
+    //e Quorum rejections reject. Quorum approvals that all agree with one of them approve, at the lowest
+    //e weight and purity among them since that's what prices the loan
+    pub fn quorum_outcome(&self, quorum: u8, tolerance_bps: u16) -> Option<QuorumOutcome> {
+        let attestations = self.active_attestations();
+        let quorum = quorum.max(1) as usize;
+
+        if attestations.iter().filter(|a| !a.approve).count() >= quorum {
+            return Some(QuorumOutcome::Rejected);
+        }
+
+        for reference in attestations.iter().filter(|a| a.approve) {
+            let agreeing = attestations
+                .iter()
+                .filter(|a| reference.agrees_with(a, tolerance_bps));
+            if agreeing.clone().count() >= quorum {
+                return Some(QuorumOutcome::Approved {
+                    weight_in_grams: agreeing.clone().map(|a| a.weight_in_grams).min()?,
+                    purity_in_bps: agreeing.map(|a| a.purity_in_bps).min()?,
+                });
+            }
+        }
+        None
+    }
+
+    //e Borrower can pull out anytime before review starts, or once review has run past the SLA without a decision
+    pub fn can_cancel(&self, current_time: i64) -> bool {
+        match self.status {
+            VerificationStatus::Pending => true,
+            VerificationStatus::UnderReview => current_time > self.review_deadline,
+            _ => false,
+        }
+    }
 }
 
 #[account]
@@ -111,7 +1267,7 @@ impl AdminRegistry {
     }
 
     pub fn add_admin(&mut self, admin: Pubkey) -> Result<()> {
-        require!(self.admins.len() <= Self::MAX_ADMINS, Errors::MaxAdmins);
+        require!(self.admins.len() < Self::MAX_ADMINS, Errors::MaxAdmins);
         require!(!self.admins.contains(&admin), Errors::AdminAlreadyExists);
         self.admins.push(admin);
         Ok(())
@@ -1,13 +1,14 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { MeridianProtocol } from "../target/types/meridian_protocol";
-import { Connection, Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
-import { ASSOCIATED_TOKEN_PROGRAM_ID, createMint, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID,TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { Connection, Keypair, PublicKey, SystemProgram, SYSVAR_INSTRUCTIONS_PUBKEY, Transaction } from "@solana/web3.js";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, createMint, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_PROGRAM_ID,TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import { createSignerFromKeypair, generateSigner, KeypairSigner, signerIdentity, some } from "@metaplex-foundation/umi";
 import { fromWeb3JsKeypair, fromWeb3JsPublicKey, toWeb3JsPublicKey } from "@metaplex-foundation/umi-web3js-adapters";
 import { createV1, fetchAssetsByOwner, MPL_CORE_PROGRAM_ID, mplCore} from "@metaplex-foundation/mpl-core";
 import { createUmi } from "@metaplex-foundation/umi-bundle-defaults";
 import { BN } from "bn.js";
+import { createHash } from "crypto";
 
 describe("meridian_protocol", () => {
   // Configure the client to use the local cluster.
@@ -43,8 +44,13 @@ describe("meridian_protocol", () => {
   let admin_registry: PublicKey;
   //mock oracle
   let mock_oracle: PublicKey;
+  let rate_model: PublicKey;
+  //protocol fee vault
+  let protocol_fee_vault: PublicKey;
   //borrower state
   let borrower_state_pda: PublicKey;
+  //borrower profile
+  let borrower_profile_pda: PublicKey;
 
   //Day 2
  
@@ -66,6 +72,7 @@ describe("meridian_protocol", () => {
   let lender_lp_ata: PublicKey;
 
   let lending_pool_bump: number;
+  const pool_id = new BN(0);
   let lending_pool_seize_vault_bump: number;
   let lending_pool_verification_vault_bump: number;
   let admin_registry_bump: number;
@@ -118,13 +125,26 @@ describe("meridian_protocol", () => {
     assetAddress = toWeb3JsPublicKey(asset.publicKey);
         console.log("Asset created at: ", assetAddress.toBase58());
 
+    //Creating mints
+    mint_usdc = await createMint(
+      connection,
+      payer.payer,
+      authority.publicKey,
+      null,
+      6
+    );
+
+    console.log(`Mint USDC created at : ${mint_usdc.toBase58()}`);
+
     //Creating pda's
 
-    //Lending Pool PDA
+    //Lending Pool PDA (keyed by owner, stablecoin mint and pool id)
     [lending_pool_pda,lending_pool_bump] = PublicKey.findProgramAddressSync(
       [
         Buffer.from("meridian_pool"),
-        authority.publicKey.toBuffer()
+        authority.publicKey.toBuffer(),
+        mint_usdc.toBuffer(),
+        pool_id.toArrayLike(Buffer, "le", 8),
       ],
       program.programId
     );
@@ -183,10 +203,33 @@ describe("meridian_protocol", () => {
     console.log("Lending Pool Mock Oracle: ",mock_oracle.toBase58());
     console.log("Mock Oracle Bump: ", mock_oracle_bump);
 
+    //Rate model, prices borrows off utilization
+    [rate_model] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("meridian_rate_model"),
+        lending_pool_pda.toBuffer(),
+      ],
+      program.programId
+    );
+
+    //Fee vault, origination, early withdrawal and reserve fees end up here
+    [protocol_fee_vault] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("meridian_fee_vault"),
+        lending_pool_pda.toBuffer(),
+      ],
+      program.programId
+    );
+
     //Borrower state pda
-    [borrower_state_pda,borrower_bump] = PublicKey.findProgramAddressSync(
+    //e Loans are indexed by the borrower's loan counter, this is the first loan (id 0)
+    [borrower_state_pda,borrower_bump] = loanPda(program.programId,lending_pool_pda,borrower.publicKey,0);
+
+    //Borrower profile pda
+    [borrower_profile_pda] = PublicKey.findProgramAddressSync(
       [
-        Buffer.from("meridian_borrower_state"),
+        Buffer.from("meridian_borrower_profile"),
+        lending_pool_pda.toBuffer(),
         borrower.publicKey.toBuffer(),
       ],
       program.programId
@@ -195,45 +238,33 @@ describe("meridian_protocol", () => {
     console.log("Borrower State Pda: ", borrower_state_pda.toBase58());
     console.log("Borrower Bump: ",borrower_bump);
 
-
-    //Creating mints
-    mint_usdc = await createMint(
-      connection,
-      payer.payer,
-      authority.publicKey,
-      null,
-      6
+    //LP Mint PDA (mint authority is the lending pool PDA)
+    [mint_lp] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("meridian_lp_mint"),
+        lending_pool_pda.toBuffer(),
+      ],
+      program.programId
     );
 
-    console.log(`Mint USDC created at : ${mint_usdc.toBase58()}`);
-
+    console.log("LP Mint PDA: ", mint_lp.toBase58());
 
-    mint_lp = await createMint(
-      connection,
-      payer.payer,
-      authority.publicKey,
-      null,
-      6
-    );
 
-    console.log(`Mint LP created at : ${mint_lp.toBase58()}`);
 
     //Creating ATA's
     lender_usdc_ata = await createAta("USDC","Lender",lender_usdc_ata,connection, lender,mint_usdc,lender.publicKey);
-    lender_lp_ata = await createAta("LP","Lender",lender_lp_ata,connection,lender,mint_lp,lender.publicKey);
+    lender_lp_ata = getAssociatedTokenAddressSync(mint_lp,lender.publicKey);
     
-    //e The owners for both LPool ATA's are Authority
-    lending_pool_usdc_ata = await createAta("USDC","Lending Pool",lending_pool_usdc_ata,connection, authority,mint_usdc,authority.publicKey);
-    lending_pool_lp_ata = await createAta("LP","Lending Pool",lending_pool_lp_ata,connection,authority,mint_lp,authority.publicKey);
+    //e The owner of both LPool ATA's is the lending pool PDA, they're created in initialize
+    lending_pool_usdc_ata = getAssociatedTokenAddressSync(mint_usdc,lending_pool_pda,true);
+    lending_pool_lp_ata = getAssociatedTokenAddressSync(mint_lp,lending_pool_pda,true);
 
     borrower_usdc_ata = await createAta("USDC","Borrower",borrower_usdc_ata,connection, borrower,mint_usdc,borrower.publicKey);
     liquidator_usdc_ata = await createAta("USDC","Liquidator",liquidator_usdc_ata,connection,liquidator,mint_usdc,liquidator.publicKey);
     
-    //Minting USDC and LP's to the necessary ATA's
-    await mintTokens("Lending Pool ATA" ,"USDC",connection,authority,mint_usdc,authority,10000000,lending_pool_usdc_ata);
-    await mintTokens("Lender USDC ATA", "USDC",connection,authority,mint_usdc,authority,1000,lender_usdc_ata);
+    //Minting USDC to the necessary ATA's
+    await mintTokens("Lender USDC ATA", "USDC",connection,authority,mint_usdc,authority,10000000,lender_usdc_ata);
     await mintTokens("Borrower USDC ATA","USDC", connection, authority,mint_usdc,authority,1000000000,borrower_usdc_ata); 
-    await mintTokens("Lending POOL LP ATA", "LP",connection,authority,mint_lp, authority,10,lending_pool_lp_ata);
   })  
   
   it("Initialize the Pool",async() => {
@@ -260,6 +291,7 @@ describe("meridian_protocol", () => {
     let withdrawal_epoch = new BN(7*86400);
 
     const tx = await program.methods.initialize(
+       pool_id,
        ltv,
        u1_bps,
        u2_bps,
@@ -284,8 +316,10 @@ describe("meridian_protocol", () => {
       lendingPool: lending_pool_pda,
       adminRegistry: admin_registry,
       mockOracle: mock_oracle,
+      rateModel: rate_model,
       lendingPoolUsdcAta: lending_pool_usdc_ata,
       lendingPoolLpAta: lending_pool_lp_ata,
+      protocolFeeVault: protocol_fee_vault,
       protocolSeizeVault: lending_pool_seize_vault_PDA,
       protocolVerificationVault: lending_pool_verification_vault,
       tokenProgram: TOKEN_PROGRAM_ID,
@@ -300,7 +334,7 @@ describe("meridian_protocol", () => {
     const pool_usdc_balance = await connection.getTokenAccountBalance(lender_usdc_ata);
     log_state("Pool USDC Balance After Initialization: ", pool_usdc_balance.value.amount);
 
-    const pool_lp_balance = await connection.getTokenAccountBalance(lender_lp_ata);
+    const pool_lp_balance = await connection.getTokenAccountBalance(lending_pool_lp_ata);
     log_state("Pool LP Balance After Initialization: ", pool_lp_balance.value.amount);
   })
 
@@ -315,6 +349,15 @@ describe("meridian_protocol", () => {
 
     console.log("Admin added succesfully : ", tx);
 
+    const tx_two = await program.methods.addAdmin(admin_two.publicKey).accountsPartial({
+      authority: authority.publicKey,
+      adminRegistry: admin_registry,
+      lendingPool: lending_pool_pda,
+      systemProgram: SystemProgram.programId,
+    }).signers([authority]).rpc();
+
+    console.log("Admin added succesfully : ", tx_two);
+
     const adminregstate = await program.account.adminRegistry.fetch(admin_registry);
     log_state("Admin registry admins : ", adminregstate.admins)
   })
@@ -345,14 +388,170 @@ describe("meridian_protocol", () => {
     log_state(`Pool Is Locked : `,  PoolState.isLocked);
   })
   
+  it("Set Borrow Limits", async() => {
+    //e 10% of pool assets kept idle for withdrawals, borrows capped at 85% utilization
+    const tx = await program.methods.setBorrowLimits(1000, 8500).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Borrow Limits Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Reserve Ratio (bps) : `, PoolState.reserveRatioBps);
+    log_state(`Max Utilization (bps) : `, PoolState.maxUtilizationBps);
+  })
+
+  it("Set Interest Mode", async() => {
+    //e Compound monthly (30 days), mode 2
+    const tx = await program.methods.setInterestMode(2, new BN(30*86400)).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Interest Mode Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Interest Mode : `, PoolState.interestMode);
+    log_state(`Compounding Period : `, PoolState.compoundingPeriod.toString());
+  })
+
+  it("Set Fixed Rate Premium", async() => {
+    //e Fixed rate loans pay 2% on top of the current tier rate
+    const tx = await program.methods.setFixedRatePremium(200).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Fixed Rate Premium Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Fixed Rate Premium (bps) : `, PoolState.fixedRatePremiumBps);
+  })
+
+  it("Set Rate Model", async() => {
+    //e Linear with a kink at 80% utilization: 2% base, +10% up to the kink, +60% above it
+    const tx = await program.methods.setRateModel(1, 200, 1000, 6000, 8000, []).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      rateModel: rate_model,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Rate Model Updated Succesfully: ",tx);
+    const RateModelState = await program.account.rateModel.fetch(rate_model);
+    log_state(`Rate Model Type : `, RateModelState.modelType);
+    log_state(`Optimal Utilization (bps) : `, RateModelState.optimalUtilizationBps);
+  })
+
+  it("Set Term Params", async() => {
+    //e Loans unpaid 3 days past maturity can be defaulted, overdue time pays an extra 8% APR
+    const tx = await program.methods.setTermParams(new BN(3*86400), 800).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      rateModel: rate_model,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Term Params Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Grace Period : `, PoolState.gracePeriod.toString());
+    log_state(`Penalty APR (bps) : `, PoolState.penaltyAprBps);
+  })
+
+  it("Set Installment Params", async() => {
+    //e 5% late fee per missed installment, defaultable after 2 missed
+    const tx = await program.methods.setInstallmentParams(500, 2).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      rateModel: rate_model,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Installment Params Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Late Fee (bps) : `, PoolState.lateFeeBps);
+    log_state(`Max Missed Installments : `, PoolState.maxMissedInstallments);
+  })
+
+  it("Set Refinance Fee", async() => {
+    //e 0.5% of the outstanding debt, charged when a loan rolls into new terms
+    const tx = await program.methods.setRefinanceFee(50).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      rateModel: rate_model,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Refinance Fee Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Refinance Fee (bps) : `, PoolState.refinanceFeeBps);
+  })
+
+  it("Set Verification Quorum", async() => {
+    //e Both admins have to agree, weights and purities within 0.5% of each other. Can't exceed the registered admins
+    const tx = await program.methods.setVerificationQuorum(2, 50).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      rateModel: rate_model,
+      adminRegistry: admin_registry,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Verification Quorum Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Verification Quorum : `, PoolState.verificationQuorum);
+    log_state(`Attestation Tolerance (bps) : `, PoolState.attestationToleranceBps);
+  })
+
+  it("Set Verification SLA", async() => {
+    //e Admins get 2 days to decide, after that the borrower can take the asset back
+    const tx = await program.methods.setVerificationSla(new BN(2*86400)).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      rateModel: rate_model,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Verification SLA Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Verification SLA : `, PoolState.verificationSla.toString());
+  })
+
+  it("Set Origination Fee Mode", async() => {
+    //e New loans get the fee deducted from the usdc they receive (1) unless the borrower opts out
+    const tx = await program.methods.setOriginationFeeMode(1).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      rateModel: rate_model,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Origination Fee Mode Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Origination Fee Mode : `, PoolState.originationFeeMode);
+  })
+
+  it("Set Reserve Factor", async() => {
+    //e 15% of the interest borrowers pay is kept as protocol reserves
+    const tx = await program.methods.setReserveFactor(1500).accountsPartial({
+      authority: authority.publicKey,
+      lendingPool: lending_pool_pda,
+      rateModel: rate_model,
+      systemProgram: SystemProgram.programId
+    }).signers([authority]).rpc();
+
+    console.log("Reserve Factor Updated Succesfully: ",tx);
+    const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+    log_state(`Reserve Factor (bps) : `, PoolState.reserveFactorBps);
+  })
+
   it("Lending", async() => {
     const lender_usdc_balance_before  = await connection.getTokenAccountBalance(lender_usdc_ata);
-    const lender_lp_balace_before = await connection.getTokenAccountBalance(lender_lp_ata);
         
    log_state("Lender USDC Balance Before Deposit: ", lender_usdc_balance_before.value.amount);
-   log_state("Lender LP State Before Balance: ", lender_lp_balace_before.value.amount);
-    const tx = await program.methods.deposit(new BN(5)).accountsPartial({
-      authority: authority.publicKey,
+    //e The lender funds the pool, borrowers draw from this liquidity
+    const tx = await program.methods.deposit(new BN(1000000*10**6)).accountsPartial({
       lender: lender.publicKey,
       mint: mint_usdc,
       mintLp: mint_lp,
@@ -364,7 +563,7 @@ describe("meridian_protocol", () => {
       tokenProgram: TOKEN_PROGRAM_ID,
       associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
       systemProgram: SystemProgram.programId,
-    }).signers([authority,lender]).rpc();
+    }).signers([lender]).rpc();
 
    console.log("Lender Deposit transaction was succesful");
    const lender_usdc_balance_after  = await connection.getTokenAccountBalance(lender_usdc_ata);
@@ -372,8 +571,89 @@ describe("meridian_protocol", () => {
     
    log_state("Lender USDC Balance After Deposit: ", lender_usdc_balance_after.value.amount);
    log_state("Lender LP State After Balance: ", lender_lp_balace_after.value.amount);
+
+   const exchange_rate = await program.methods.getLpExchangeRate().accountsPartial({
+     lendingPool: lending_pool_pda,
+   }).view();
+   log_state("LP Exchange Rate (1e18): ", exchange_rate.toString());
+
+   const lender_state = await program.account.lender.fetch(PublicKey.findProgramAddressSync(
+     [Buffer.from("lender_seed"), lending_pool_pda.toBuffer(), lender.publicKey.toBuffer()],
+     program.programId
+   )[0]);
+   log_state("Lender deposit lots: ", lender_state.lots.length);
+  })
+
+
+  it("Partial Withdraw", async() => {
+    const lender_usdc_balance_before = Number((await connection.getTokenAccountBalance(lender_usdc_ata)).value.amount);
+    const lender_state_pda = PublicKey.findProgramAddressSync(
+      [Buffer.from("lender_seed"), lending_pool_pda.toBuffer(), lender.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    //e Still inside the withdrawal epoch, so the early withdrawal fee (1%) applies
+    let lp_shares = new BN(1000*10**6);
+    let min_amount_out = new BN(990*10**6);
+    const tx = await program.methods.withdraw(lp_shares,min_amount_out).accountsPartial({
+      lender: lender.publicKey,
+      mint: mint_usdc,
+      mintLp: mint_lp,
+      lendingPool: lending_pool_pda,
+      lendingPoolUsdcAta: lending_pool_usdc_ata,
+      lendingPoolLpAta: lending_pool_lp_ata,
+      lenderState: lender_state_pda,
+      lenderUsdcAta: lender_usdc_ata,
+      lenderLpAta: lender_lp_ata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    }).signers([lender]).rpc();
+
+    console.log("Partial withdrawal was succesful: ", tx);
+    const lender_usdc_balance_after = Number((await connection.getTokenAccountBalance(lender_usdc_ata)).value.amount);
+    log_state("USDC received for 1000 LP shares: ", lender_usdc_balance_after - lender_usdc_balance_before);
+
+    const lender_state = await program.account.lender.fetch(lender_state_pda);
+    log_state("Lender LP shares left: ", lender_state.lpShares.toString());
   })
 
+  it("Request Withdrawal", async() => {
+    const lender_state_pda = PublicKey.findProgramAddressSync(
+      [Buffer.from("lender_seed"), lending_pool_pda.toBuffer(), lender.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const pool_state = await program.account.lendingPool.fetch(lending_pool_pda);
+    const withdrawal_request_pda = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("meridian_withdrawal_request"),
+        lending_pool_pda.toBuffer(),
+        pool_state.withdrawalQueueTail.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+    //e Shares are escrowed now and can be claimed once the withdrawal epoch is over
+    const tx = await program.methods.requestWithdrawal(new BN(1000*10**6)).accountsPartial({
+      lender: lender.publicKey,
+      mintLp: mint_lp,
+      lendingPool: lending_pool_pda,
+      lenderState: lender_state_pda,
+      withdrawalRequest: withdrawal_request_pda,
+      lendingPoolLpAta: lending_pool_lp_ata,
+      lenderLpAta: lender_lp_ata,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    }).signers([lender]).rpc();
+
+    console.log("Withdrawal request queued: ", tx);
+    const request = await program.account.withdrawalRequest.fetch(withdrawal_request_pda);
+    log_state("Escrowed LP shares: ", request.lpShares.toString());
+    log_state("Claimable at: ", request.claimableAt.toString());
+    const pool_lp_balance = await connection.getTokenAccountBalance(lending_pool_lp_ata);
+    log_state("Pool LP Vault Balance: ", pool_lp_balance.value.amount);
+  })
 
  //Admin updates the oracle values for test purposes..
  it("Update Oracle Values", async() => {
@@ -405,20 +685,15 @@ describe("meridian_protocol", () => {
   //Deposit asset for verification
   try {
     const tx_deposit_for_verification = await program.methods.depositCollateralForVerification().accountsPartial({
-    authority: authority.publicKey,
     borrower: borrower.publicKey,
-    mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
-    lendingPoolUsdcAta: lending_pool_usdc_ata,
-    borrowerUsdcAta: borrower_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
+    borrowerState: borrower_state_pda,
     protocolVerificationVault: lending_pool_verification_vault,
     rwaAsset: asset.publicKey,
-    mockOracle: mock_oracle,
-    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-    tokenProgram: TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     mplCoreProgram: MPL_CORE_PROGRAM_ID,
-   }).signers([authority,borrower]).rpc();
+   }).signers([borrower]).rpc();
 
    console.log(`Succesfully deposited asset for verification: ${tx_deposit_for_verification}`);
   } catch (error) {
@@ -438,10 +713,27 @@ describe("meridian_protocol", () => {
 
   console.log("The verification id for the asset is: ",verification_id);
 
+  //e Admin picks the request up first, after this the borrower can no longer cancel it
+  const review_tx = await program.methods.startVerificationReview().accountsPartial({
+    signer: admin_one.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    lendingPoolUsdcAta: lending_pool_usdc_ata,
+    adminRegistry: admin_registry,
+    borrowerState: borrower_state_pda,
+    borrowerUsdcAta: borrower_usdc_ata,
+    rwaAsset: asset.publicKey,
+    protocolVerificationVault: lending_pool_verification_vault,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    mplCoreProgram: MPL_CORE_PROGRAM_ID,
+  }).signers([admin_one]).rpc();
+  console.log(`Verification review started: ${review_tx}`);
+
   //Verifying the asset
-  let is_verified = false;
-  //e the purity wass wrong
-  const verify_asset_tx = await program.methods.verifyAsset(verification_id,is_verified,9999,new BN(2000)).accountsPartial({
+  let is_verified = true;
+  const verify_asset_tx = await program.methods.verifyAsset(verification_id,is_verified,9999,new BN(2000), assayReport(admin_one.publicKey)).accountsPartial({
     signer: admin_one.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
@@ -457,19 +749,40 @@ describe("meridian_protocol", () => {
     mplCoreProgram: MPL_CORE_PROGRAM_ID,
   }).signers([admin_one]).rpc();
 
+  //e Second attestation from admin two, 0.25% lighter but within tolerance, reaches the quorum.
+  //e The lower weight is the one that sticks
+  const second_attestation_tx = await program.methods.verifyAsset(verification_id,is_verified,9999,new BN(1995), assayReport(admin_two.publicKey)).accountsPartial({
+    signer: admin_two.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    lendingPoolUsdcAta: lending_pool_usdc_ata,
+    adminRegistry: admin_registry,
+    borrowerState: borrower_state_pda,
+    borrowerUsdcAta: borrower_usdc_ata,
+    rwaAsset: asset.publicKey,
+    protocolVerificationVault: lending_pool_verification_vault,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    mplCoreProgram: MPL_CORE_PROGRAM_ID,
+  }).signers([admin_two]).rpc();
+  console.log(`Second attestation submitted: ${second_attestation_tx}`);
+
   const borrower_state_after = await program.account.loanState.fetch(borrower_state_pda);
   console.log(`Asset is succesfully verified: ${verify_asset_tx}`);
-  const is_asset_verified = borrower_state_after.isVerified;
-  console.log(`Asset verification status: ${is_asset_verified}`); 
+  const [verification_request_pda] = verificationRequestPda(program.programId, borrower_state_pda);
+  const verification_request = await program.account.verificationRequest.fetch(verification_request_pda);
+  console.log(`Asset verification status: ${JSON.stringify(verification_request.status)}`);
+  console.log(`Assay report: bar ${verification_request.report.barSerial} from ${verification_request.report.refinery}, ${verification_request.attestationCount} attestations`);
 
   //Depositing collateral
   const deposit_collateral = await program.methods.depositCollateral().accountsPartial({
-    authority: authority.publicKey,
     borrower: borrower.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
     lendingPoolUsdcAta: lending_pool_usdc_ata,
     borrowerUsdcAta: borrower_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
     borrowerState: borrower_state_pda,
     protocolVerificationVault: lending_pool_verification_vault,
     rwaAsset: asset.publicKey,
@@ -478,7 +791,7 @@ describe("meridian_protocol", () => {
     tokenProgram: TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     mplCoreProgram: MPL_CORE_PROGRAM_ID,
- }).signers([authority,borrower]).rpc();
+ }).signers([borrower]).rpc();
 
  
  let borrower_state_three = await program.account.loanState.fetch(borrower_state_pda);
@@ -488,12 +801,31 @@ describe("meridian_protocol", () => {
 
   console.log("Borrower USDC Balance before borrowing", (await connection.getTokenAccountBalance(borrower_usdc_ata)).value.amount);
 
-  const borrow_tx = await program.methods.borrowAssets().accountsPartial({
-    authority: authority.publicKey,
+  //e Borrowing below the max LTV so there's headroom left to draw later, rate fixed at origination (0), 180 day term, bullet repayment (0),
+  //e origination fee capitalized into the debt (0) instead of the pool default
+  const borrow_tx = await program.methods.borrowAssets(new BN(50_000), 0, new BN(180*86400), 0, 0).accountsPartial({
+    borrower: borrower.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    lendingPoolUsdcAta: lending_pool_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
+    borrowerState: borrower_state_pda,
+    borrowerUsdcAta: borrower_usdc_ata,
+    rwaAsset: assetAddress,
+    protocolVerificationVault: lending_pool_verification_vault,
+    mockOracle: mock_oracle,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    mplCoreProgram: MPL_CORE_PROGRAM_ID,
+  }).signers([borrower]).rpc();
+
+  const draw_tx = await program.methods.drawAdditional(new BN(20_000)).accountsPartial({
     borrower: borrower.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
     lendingPoolUsdcAta: lending_pool_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
     borrowerState: borrower_state_pda,
     borrowerUsdcAta: borrower_usdc_ata,
     rwaAsset: assetAddress,
@@ -503,7 +835,8 @@ describe("meridian_protocol", () => {
     tokenProgram: TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     mplCoreProgram: MPL_CORE_PROGRAM_ID,
-  }).signers([authority, borrower]).rpc();
+  }).signers([borrower]).rpc();
+  console.log(`Drew additional successfully: ${draw_tx}`);
 
   console.log(`Borrowed successfully: ${borrow_tx}`);
   
@@ -512,6 +845,79 @@ describe("meridian_protocol", () => {
    
 });
 
+ it("Accrue Interest", async() => {
+   //e Permissionless, any wallet can checkpoint the pool
+   const tx = await program.methods.accrueInterest().accountsPartial({
+    lendingPool: lending_pool_pda,
+   }).rpc();
+
+   const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+   log_state("Interest accrued: ", tx);
+   log_state("Borrow Index: ", PoolState.borrowIndex.toString());
+   log_state("Total Interest Accrued: ", PoolState.totalInterestAccrued.toString());
+ });
+
+ it("Get Rates", async() => {
+   const rates = await program.methods.getRates().accountsPartial({
+    lendingPool: lending_pool_pda,
+    rateModel: rate_model,
+   }).view();
+
+   log_state("Utilization (bps): ", rates.utilizationBps.toString());
+   log_state("Borrow APR (bps): ", rates.borrowAprBps);
+   log_state("Supply APY (bps): ", rates.supplyApyBps.toString());
+ });
+
+ it("Flash Loan", async() => {
+   //e Borrow and repay in the same transaction, the borrower covers the flash fee out of their own usdc
+   const flash_accounts = {
+    borrower: borrower.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    lendingPoolUsdcAta: lending_pool_usdc_ata,
+    borrowerUsdcAta: borrower_usdc_ata,
+    instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+   };
+   const pool_before = await program.account.lendingPool.fetch(lending_pool_pda);
+
+   const flash_borrow_ix = await program.methods.flashBorrow(new BN(10_000*10**6)).accountsPartial(flash_accounts).instruction();
+   const flash_repay_ix = await program.methods.flashRepay().accountsPartial(flash_accounts).instruction();
+   const tx = await provider.sendAndConfirm(new Transaction().add(flash_borrow_ix, flash_repay_ix), [borrower]);
+
+   const pool_after = await program.account.lendingPool.fetch(lending_pool_pda);
+   log_state("Flash loan: ", tx);
+   log_state("Flash fee earned by LPs: ", pool_after.totalIdleUsdc.sub(pool_before.totalIdleUsdc).toString());
+   log_state("Flash Loan Active: ", pool_after.flashLoanActive);
+ });
+
+ it("Refinance Loan", async() => {
+   //e Rolling the open loan into a variable rate (1), 365 day term, bullet repayment (0), the collateral stays in the pool
+   const tx = await program.methods.refinanceLoan(1, new BN(365*86400), 0).accountsPartial({
+    borrower: borrower.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    lendingPoolUsdcAta: lending_pool_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
+    borrowerState: borrower_state_pda,
+    borrowerUsdcAta: borrower_usdc_ata,
+    rwaAsset: assetAddress,
+    protocolVerificationVault: lending_pool_verification_vault,
+    mockOracle: mock_oracle,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    mplCoreProgram: MPL_CORE_PROGRAM_ID,
+   }).signers([borrower]).rpc();
+
+   const LoanState = await program.account.loanState.fetch(borrower_state_pda);
+   log_state("Loan refinanced: ", tx);
+   log_state("Principal: ", LoanState.principalBorrowed.toString());
+   log_state("Borrow APR (bps): ", LoanState.borrowAprBps);
+   log_state("Maturity: ", LoanState.maturityTs.toString());
+ });
+
  it("Repay assets", async() => {
    //e calculating total debt left..
 
@@ -552,11 +958,35 @@ describe("meridian_protocol", () => {
 
    log_state("Lending Pool PDA Address:" ,lending_pool_pda.toBase58());
 
+  //e Paying down part of the debt first, the collateral stays with the pool
+  const partial_repay_tx = await program.methods.repayDebt(new BN(10_000)).accountsPartial({
+    borrower: borrower.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    lendingPoolUsdcAta: lending_pool_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
+    borrowerState: borrower_state_pda,
+    borrowerUsdcAta: borrower_usdc_ata,
+    rwaAsset: asset.publicKey,
+    protocolVerificationVault: lending_pool_verification_vault,
+    mockOracle: mock_oracle,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    mplCoreProgram: MPL_CORE_PROGRAM_ID
+  }).signers([borrower]).rpc();
+  const loan_after_partial = await program.account.loanState.fetch(borrower_state_pda);
+  log_state("Partially repaid: ", partial_repay_tx);
+  log_state("Debt left after partial repay: ", loan_after_partial.totalDebtToRepay.toString());
+  log_state("Principal left after partial repay: ", loan_after_partial.principalBorrowed.toString());
+
+  //e Overpaying is capped at the remaining debt, which releases the collateral
   const repay_tx = await program.methods.repayDebt(total_debt_left).accountsPartial({
     borrower: borrower.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
     lendingPoolUsdcAta: lending_pool_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
     borrowerState: borrower_state_pda,
     borrowerUsdcAta: borrower_usdc_ata,
     rwaAsset: asset.publicKey,
@@ -581,24 +1011,62 @@ describe("meridian_protocol", () => {
   log_state("Borrower USDC ATA Balance After: ", borrowerAfter);
  });
 
- it("Liquidate", async() => {
-   console.log("Liquidation setup.....");
-  //e depositing collateral for verification..
-   const tx_deposit_for_verification = await program.methods.depositCollateralForVerification().accountsPartial({
-    authority: authority.publicKey,
+ it("Cancel Verification", async() => {
+   //e The asset was handed back on repay, it's pledged under a new loan (id 1) and pulled out again before any admin reviews it
+   [borrower_state_pda] = loanPda(program.programId,lending_pool_pda,borrower.publicKey,1);
+   const deposit_tx = await program.methods.depositCollateralForVerification().accountsPartial({
+    borrower: borrower.publicKey,
+    lendingPool: lending_pool_pda,
+    borrowerProfile: borrower_profile_pda,
+    borrowerState: borrower_state_pda,
+    protocolVerificationVault: lending_pool_verification_vault,
+    rwaAsset: asset.publicKey,
+    systemProgram: SystemProgram.programId,
+    mplCoreProgram: MPL_CORE_PROGRAM_ID,
+   }).signers([borrower]).rpc();
+   console.log(`Succesfully deposited asset for verification: ${deposit_tx}`);
+
+   const cancel_tx = await program.methods.cancelVerification().accountsPartial({
     borrower: borrower.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
     lendingPoolUsdcAta: lending_pool_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
+    borrowerState: borrower_state_pda,
     borrowerUsdcAta: borrower_usdc_ata,
-    protocolVerificationVault: lending_pool_verification_vault,
     rwaAsset: asset.publicKey,
+    protocolVerificationVault: lending_pool_verification_vault,
     mockOracle: mock_oracle,
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
     tokenProgram: TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     mplCoreProgram: MPL_CORE_PROGRAM_ID,
-   }).signers([authority,borrower]).rpc();
+   }).signers([borrower]).rpc();
+
+   const [verification_request_pda] = verificationRequestPda(program.programId, borrower_state_pda);
+   //e The request stays around as the record of the cancellation
+   const verification_request = await program.account.verificationRequest.fetch(verification_request_pda);
+   log_state("Verification cancelled: ", cancel_tx);
+   log_state("Verification status: ", JSON.stringify(verification_request.status));
+   log_state("Cancelled at: ", verification_request.cancelledAt.toString());
+   log_state("Returned at: ", verification_request.returnedAt.toString());
+ });
+
+ it("Liquidate", async() => {
+   console.log("Liquidation setup.....");
+  //e depositing collateral for verification..
+   //e The asset is back with the borrower after the cancellation, so it's pledged again under a new loan (id 2)
+   [borrower_state_pda] = loanPda(program.programId,lending_pool_pda,borrower.publicKey,2);
+   const tx_deposit_for_verification = await program.methods.depositCollateralForVerification().accountsPartial({
+    borrower: borrower.publicKey,
+    lendingPool: lending_pool_pda,
+    borrowerProfile: borrower_profile_pda,
+    borrowerState: borrower_state_pda,
+    protocolVerificationVault: lending_pool_verification_vault,
+    rwaAsset: asset.publicKey,
+    systemProgram: SystemProgram.programId,
+    mplCoreProgram: MPL_CORE_PROGRAM_ID,
+   }).signers([borrower]).rpc();
 
    console.log(`Succesfully deposited asset for verification: ${tx_deposit_for_verification}`);
 
@@ -611,9 +1079,9 @@ describe("meridian_protocol", () => {
   console.log("The verification id for the asset is: ",verification_id);
 
   //Verifying the asset
-  let is_verified = false;
-  //e the purity wass wrong
-  const verify_asset_tx = await program.methods.verifyAsset(verification_id,is_verified,9999,new BN(2000)).accountsPartial({
+  //e Approved straight from pending, the decision moves it through review on its own
+  let is_verified = true;
+  const verify_asset_tx = await program.methods.verifyAsset(verification_id,is_verified,9999,new BN(2000), assayReport(admin_one.publicKey)).accountsPartial({
     signer: admin_one.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
@@ -629,19 +1097,40 @@ describe("meridian_protocol", () => {
     mplCoreProgram: MPL_CORE_PROGRAM_ID,
   }).signers([admin_one]).rpc();
 
+  //e Second attestation from admin two, 0.25% lighter but within tolerance, reaches the quorum.
+  //e The lower weight is the one that sticks
+  const second_attestation_tx = await program.methods.verifyAsset(verification_id,is_verified,9999,new BN(1995), assayReport(admin_two.publicKey)).accountsPartial({
+    signer: admin_two.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    lendingPoolUsdcAta: lending_pool_usdc_ata,
+    adminRegistry: admin_registry,
+    borrowerState: borrower_state_pda,
+    borrowerUsdcAta: borrower_usdc_ata,
+    rwaAsset: asset.publicKey,
+    protocolVerificationVault: lending_pool_verification_vault,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+    mplCoreProgram: MPL_CORE_PROGRAM_ID,
+  }).signers([admin_two]).rpc();
+  console.log(`Second attestation submitted: ${second_attestation_tx}`);
+
   const borrower_state_after = await program.account.loanState.fetch(borrower_state_pda);
   console.log(`Asset is succesfully verified: ${verify_asset_tx}`);
-  const is_asset_verified = borrower_state_after.isVerified;
-  console.log(`Asset verification status: ${is_asset_verified}`); 
+  const [verification_request_pda] = verificationRequestPda(program.programId, borrower_state_pda);
+  const verification_request = await program.account.verificationRequest.fetch(verification_request_pda);
+  console.log(`Asset verification status: ${JSON.stringify(verification_request.status)}`);
+  console.log(`Assay report: bar ${verification_request.report.barSerial} from ${verification_request.report.refinery}, ${verification_request.attestationCount} attestations`);
 
     //Depositing collateral
   const deposit_collateral = await program.methods.depositCollateral().accountsPartial({
-    authority: authority.publicKey,
     borrower: borrower.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
     lendingPoolUsdcAta: lending_pool_usdc_ata,
     borrowerUsdcAta: borrower_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
     borrowerState: borrower_state_pda,
     protocolVerificationVault: lending_pool_verification_vault,
     rwaAsset: asset.publicKey,
@@ -650,7 +1139,7 @@ describe("meridian_protocol", () => {
     tokenProgram: TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     mplCoreProgram: MPL_CORE_PROGRAM_ID,
- }).signers([authority,borrower]).rpc();
+ }).signers([borrower]).rpc();
 
  
  let borrower_state_three = await program.account.loanState.fetch(borrower_state_pda);
@@ -661,12 +1150,13 @@ describe("meridian_protocol", () => {
  let value_before: number = Number((await connection.getTokenAccountBalance(borrower_usdc_ata)).value.amount);
   console.log("Borrower USDC Balance before borrowing", value_before);
 
-  const borrow_tx = await program.methods.borrowAssets().accountsPartial({
-    authority: authority.publicKey,
+  //e No fee mode passed, so the pool default applies and the fee comes out of the disbursement
+  const borrow_tx = await program.methods.borrowAssets(new BN(90_000), 1, new BN(90*86400), 1, null).accountsPartial({
     borrower: borrower.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
     lendingPoolUsdcAta: lending_pool_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
     borrowerState: borrower_state_pda,
     borrowerUsdcAta: borrower_usdc_ata,
     rwaAsset: assetAddress,
@@ -676,7 +1166,12 @@ describe("meridian_protocol", () => {
     tokenProgram: TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     mplCoreProgram: MPL_CORE_PROGRAM_ID,
-  }).signers([authority, borrower]).rpc();
+  }).signers([borrower]).rpc();
+
+  //e Variable rate, repaid in 3 monthly installments
+  const installment_loan = await program.account.loanState.fetch(borrower_state_pda);
+  log_state("Installment Count: ", installment_loan.installmentCount);
+  log_state("Installment Amount: ", installment_loan.installmentAmount.toString());
 
   console.log(`Borrowed successfully: ${borrow_tx}`);
   
@@ -737,12 +1232,12 @@ describe("meridian_protocol", () => {
   log_state("Current health factor latest: ", health_factor);
 
   const liquidate_tx = await program.methods.liquidate().accountsPartial({
-    authority: authority.publicKey,
     liquidator: liquidator.publicKey,
     mintUsdc: mint_usdc,
     lendingPool: lending_pool_pda,
     lendingPoolUsdcAta: lending_pool_usdc_ata,
     liquidatorUsdcAta: liquidator_usdc_ata,
+    borrowerProfile: borrower_profile_pda,
     borrowerState: borrower_state_pda,
     rwaAsset: asset.publicKey,
     protocolSeizeVault: lending_pool_seize_vault_PDA,
@@ -751,12 +1246,65 @@ describe("meridian_protocol", () => {
     tokenProgram: TOKEN_PROGRAM_ID,
     systemProgram: SystemProgram.programId,
     mplCoreProgram: MPL_CORE_PROGRAM_ID
-  }).signers([authority,liquidator]).rpc();
+  }).signers([liquidator]).rpc();
 
   const liquidator_balance_after_liquidation: number= Number((await connection.getTokenAccountBalance(liquidator_usdc_ata)).value.amount);
   log_state("Liquidator USDC Balance after Liquidation...", liquidator_balance_after_liquidation);
   log_state("Liquidation Penalty Received: ",liquidator_balance_after_liquidation - liquidator_balance_before_liquidation); 
  });
+
+ it("Settle Liquidation Fees", async() => {
+   //e The seized asset is sold off-chain, the owner brings the protocol's share of the penalty into the fee vault
+   const accrued = (await program.account.lendingPool.fetch(lending_pool_pda)).liquidationFeesAccrued;
+   log_state("Liquidation Fees Accrued: ", accrued.toString());
+   if (accrued.isZero()) {
+    return;
+   }
+
+   const authority_usdc_ata = await createAta("USDC","Authority",null,connection,authority,mint_usdc,authority.publicKey);
+   await mintTo(connection, authority, mint_usdc, authority_usdc_ata, authority, BigInt(accrued.toString()));
+
+   const tx = await program.methods.settleLiquidationFees(accrued).accountsPartial({
+    authority: authority.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    protocolFeeVault: protocol_fee_vault,
+    source: authority_usdc_ata,
+    tokenProgram: TOKEN_PROGRAM_ID,
+   }).signers([authority]).rpc();
+
+   const PoolState = await program.account.lendingPool.fetch(lending_pool_pda);
+   log_state("Liquidation fees settled: ", tx);
+   log_state("Liquidation Fees Collected: ", PoolState.liquidationFeesCollected.toString());
+   log_state("Liquidation Fees Still Accrued: ", PoolState.liquidationFeesAccrued.toString());
+ });
+
+ it("Withdraw Protocol Fees", async() => {
+   const fees = await program.methods.getProtocolFees().accountsPartial({
+    lendingPool: lending_pool_pda,
+    rateModel: rate_model,
+   }).view();
+   log_state("Origination Fees Collected: ", fees.originationFees.toString());
+   log_state("Early Withdrawal Fees Collected: ", fees.earlyWithdrawalFees.toString());
+   log_state("Liquidation Fees Collected: ", fees.liquidationFees.toString());
+   log_state("Liquidation Fees Accrued: ", fees.liquidationFeesAccrued.toString());
+   log_state("Reserves Accrued: ", fees.reservesAccrued.toString());
+   log_state("Reserves Collected: ", fees.reserves.toString());
+   log_state("Fee Vault Balance: ", (await connection.getTokenAccountBalance(protocol_fee_vault)).value.amount);
+
+   //e Sweeping everything collected so far, the destination can be any usdc account
+   const tx = await program.methods.withdrawProtocolFees(fees.available).accountsPartial({
+    authority: authority.publicKey,
+    mintUsdc: mint_usdc,
+    lendingPool: lending_pool_pda,
+    protocolFeeVault: protocol_fee_vault,
+    destination: lender_usdc_ata,
+    tokenProgram: TOKEN_PROGRAM_ID,
+   }).signers([authority]).rpc();
+
+   log_state("Protocol fees withdrawn: ", tx);
+   log_state("Fee Vault Balance After: ", (await connection.getTokenAccountBalance(protocol_fee_vault)).value.amount);
+ });
 })
 
 
@@ -770,6 +1318,41 @@ function log_state(str: String, state: any) {
   console.log(`${str} : ${state}`)
 }
 
+//e Assay report backing an attestation, the document only lives off-chain so just its hash goes on-chain
+function assayReport(assayer: PublicKey) {
+  const document = `Assay report MRD-0001 by ${assayer.toBase58()}`;
+  return {
+    reportHash: Array.from(createHash("sha256").update(document).digest()),
+    reportUri: "https://example.com/assays/MRD-0001.pdf",
+    assayer: assayer,
+    barSerial: "MRD-0001",
+    refinery: "Meridian Test Refinery",
+    assayedAt: new BN(Math.floor(Date.now() / 1000) - 3600),
+  };
+}
+
+function verificationRequestPda(programId: PublicKey, borrower_state: PublicKey) {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("meridian_verification_request"),
+      borrower_state.toBuffer(),
+    ],
+    programId
+  );
+}
+
+function loanPda(programId: PublicKey, lending_pool: PublicKey, borrower: PublicKey, loan_id: number) {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("meridian_borrower_state"),
+      lending_pool.toBuffer(),
+      borrower.toBuffer(),
+      new BN(loan_id).toArrayLike(Buffer, "le", 8),
+    ],
+    programId
+  );
+}
+
 async function generateKeypair(name: String,keypair: Keypair) {
   keypair = Keypair.generate();
   console.log(`Keypair generated for the account: ${name} with the publickey: ${keypair.publicKey}`);